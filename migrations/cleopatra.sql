@@ -8,7 +8,10 @@ CREATE TABLE IF NOT EXISTS execution (
     name TEXT NOT NULL,
     tag TEXT,
     created_by TEXT,
-    time_created INTEGER NOT NULL
+    time_created INTEGER NOT NULL,
+    labels TEXT, -- JSON object of free-form key/value labels
+    time_finished INTEGER, -- set when the client finishes the execution
+    time_received INTEGER NOT NULL DEFAULT 0 -- server time of the creation, used by sorting and the retention
 );
 
 -- =========================================================
@@ -26,10 +29,207 @@ CREATE TABLE IF NOT EXISTS test_result (
     log TEXT,
     screenshot_id INTEGER,
     created_by TEXT,
-    time_created INTEGER NOT NULL,
+    time_created INTEGER NOT NULL, -- set by the client, the server time when omitted
+    time_received INTEGER NOT NULL DEFAULT 0, -- server time of the latest ingestion, used by the retention
+    failure_signature TEXT,
+    quarantined INTEGER NOT NULL DEFAULT 0, -- tagged by the writer when a quarantine entry matched
+    log_size INTEGER, -- bytes of the log, NULL without log
+    test_case_id INTEGER, -- the catalog entry of the test, set by the writer
     CONSTRAINT uq_test_result_execution_name UNIQUE (execution_id, name)
 );
 
 -- Indexes for common queries
 CREATE INDEX IF NOT EXISTS idx_test_result_execution_id ON test_result (execution_id);
 CREATE INDEX IF NOT EXISTS idx_execution_name ON execution (name);
+CREATE INDEX IF NOT EXISTS idx_execution_time_received ON execution (time_received);
+CREATE INDEX IF NOT EXISTS idx_test_result_failure_signature ON test_result (failure_signature);
+CREATE INDEX IF NOT EXISTS idx_test_result_name_platform_time ON test_result (name, platform, time_created);
+CREATE INDEX IF NOT EXISTS idx_test_result_name_platform_received ON test_result (name, platform, time_received);
+CREATE INDEX IF NOT EXISTS idx_test_result_execution_time ON test_result (execution_id, execution_time);
+CREATE INDEX IF NOT EXISTS idx_test_result_time_received ON test_result (time_received);
+CREATE INDEX IF NOT EXISTS idx_test_result_test_case_id ON test_result (test_case_id);
+
+-- =========================================================
+-- test_case: catalog of tests across executions, one row per name and platform
+-- added by the writer on first sight, kept by the data retention
+-- =========================================================
+CREATE TABLE IF NOT EXISTS test_case (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    platform TEXT NOT NULL,
+    description TEXT,
+    owner TEXT,
+    component TEXT,
+    suite_path TEXT, -- e.g. the file or class of the test, reported by the client
+    time_created INTEGER NOT NULL, -- first ingestion of the test
+    last_seen INTEGER NOT NULL, -- latest ingestion of the test
+    CONSTRAINT uq_test_case_name_platform UNIQUE (name, platform)
+);
+
+-- =========================================================
+-- test_log: logs above the offload threshold, compressed
+-- test_result.log is NULL for them, kept in sync by the writer
+-- =========================================================
+CREATE TABLE IF NOT EXISTS test_log (
+    test_result_id INTEGER PRIMARY KEY,
+    encoding TEXT NOT NULL, -- codec of content
+    size INTEGER NOT NULL, -- bytes of the uncompressed log
+    content BLOB NOT NULL
+);
+
+-- =========================================================
+-- test_step: steps within a test result, e.g. BDD steps
+-- replaced by the writer with every run of the test
+-- =========================================================
+CREATE TABLE IF NOT EXISTS test_step (
+    test_result_id INTEGER NOT NULL,
+    position INTEGER NOT NULL, -- order of the step within the test, from 0
+    name TEXT NOT NULL,
+    status CHAR(2) NOT NULL,
+    duration INTEGER,
+    message TEXT,
+    PRIMARY KEY (test_result_id, position)
+);
+
+-- =========================================================
+-- ownership_rule: teams owning the tests matching a pattern, added by the admin API
+-- checked after the rules of the config, in id order
+-- =========================================================
+CREATE TABLE IF NOT EXISTS ownership_rule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    team TEXT NOT NULL,
+    field TEXT NOT NULL, -- name or suite_path
+    kind TEXT NOT NULL, -- glob or regex
+    pattern TEXT NOT NULL,
+    time_created INTEGER NOT NULL
+);
+
+-- =========================================================
+-- webhook: endpoints notified of execution events
+-- =========================================================
+CREATE TABLE IF NOT EXISTS webhook (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL, -- key of the HMAC-SHA256 signature of the payloads
+    events TEXT NOT NULL, -- JSON array of the subscribed event types
+    min_pass_rate REAL, -- threshold of execution.threshold_breached
+    time_created INTEGER NOT NULL
+);
+
+-- =========================================================
+-- webhook_delivery: one event to send to one webhook, retried with backoff by the background task
+-- =========================================================
+CREATE TABLE IF NOT EXISTS webhook_delivery (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL, -- JSON body, signed as is
+    status TEXT NOT NULL, -- pending, delivered or failed
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER NOT NULL,
+    last_status INTEGER, -- http status of the latest attempt
+    last_error TEXT,
+    time_created INTEGER NOT NULL,
+    time_delivered INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_delivery_due ON webhook_delivery (status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_delivery_webhook_id ON webhook_delivery (webhook_id);
+
+-- =========================================================
+-- execution_stats: summary counters per execution and platform
+-- maintained by the writer and status updates, so summaries don't scan test_result
+-- =========================================================
+CREATE TABLE IF NOT EXISTS execution_stats (
+    execution_id INTEGER NOT NULL,
+    platform TEXT NOT NULL,
+    total INTEGER NOT NULL DEFAULT 0,
+    pass INTEGER NOT NULL DEFAULT 0,
+    fail INTEGER NOT NULL DEFAULT 0,
+    ignor INTEGER NOT NULL DEFAULT 0,
+    error INTEGER NOT NULL DEFAULT 0,
+    skip INTEGER NOT NULL DEFAULT 0,
+    block INTEGER NOT NULL DEFAULT 0,
+    known INTEGER NOT NULL DEFAULT 0,
+    total_duration INTEGER NOT NULL DEFAULT 0,
+    timed INTEGER NOT NULL DEFAULT 0, -- test results reporting execution_time
+    retried INTEGER NOT NULL DEFAULT 0,
+    flaky INTEGER NOT NULL DEFAULT 0,
+    quarantined INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (execution_id, platform, quarantined)
+);
+
+-- =========================================================
+-- quarantine: known-flaky tests whose results don't count in the summaries
+-- a missing platform matches every platform, a missing expiry never expires
+-- an entry of one platform is keyed by the test case once the test is in the catalog
+-- =========================================================
+CREATE TABLE IF NOT EXISTS quarantine (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    platform TEXT,
+    test_case_id INTEGER,
+    reason TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    expires_at INTEGER,
+    time_created INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantine_name ON quarantine (name);
+CREATE INDEX IF NOT EXISTS idx_quarantine_test_case_id ON quarantine (test_case_id);
+
+-- =========================================================
+-- idempotency_key: keys of the requests and result lines already handled
+-- scope is the request (method and path) or the execution of a result line,
+-- the response is NULL while the request is in progress and for result lines,
+-- claimed_at is NULL for result lines, whose keys are never taken over
+-- =========================================================
+CREATE TABLE IF NOT EXISTS idempotency_key (
+    scope TEXT NOT NULL,
+    key TEXT NOT NULL,
+    response_status INTEGER,
+    response_body TEXT,
+    request_hash TEXT, -- SHA-256 of the request body, set with the response
+    expires_at INTEGER NOT NULL,
+    claimed_at INTEGER, -- when the request in progress took the key
+    PRIMARY KEY (scope, key)
+);
+
+-- =========================================================
+-- artifact_blob: one stored file per distinct content, named by its SHA-256
+-- a blob without test_artifact rows is removed by the sweeper
+-- =========================================================
+CREATE TABLE IF NOT EXISTS artifact_blob (
+    sha256 TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    time_created INTEGER NOT NULL
+);
+
+-- =========================================================
+-- test_artifact: attachments of a test result (screenshots, videos, files)
+-- several attachments may share one blob
+-- =========================================================
+CREATE TABLE IF NOT EXISTS test_artifact (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    test_result_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL, -- screenshot, video, log or file
+    content_type TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    time_created INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_test_artifact_test_result_id ON test_artifact (test_result_id);
+CREATE INDEX IF NOT EXISTS idx_test_artifact_sha256 ON test_artifact (sha256);
+
+-- =========================================================
+-- test_result_fts: full-text index over test name, description and log
+-- rowid mirrors test_result.id, kept in sync by the writer
+-- =========================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS test_result_fts USING fts5 (
+    name,
+    description,
+    log,
+    tokenize = 'unicode61'
+);
@@ -0,0 +1,144 @@
+// src/artifact.rs
+// Content-addressed storage of artifact blobs on the local filesystem
+
+use axum::body::Bytes;
+use futures::{Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::{fs, io::AsyncWriteExt};
+
+use crate::config::ArtifactConfig;
+use crate::database::delete_orphan_blobs;
+
+// Uploads are written here before they are moved to their content path
+const STAGING_DIR: &str = "staging";
+
+// Distinguishes the staging files of concurrent uploads
+static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+pub enum StoreError {
+    /// The upload exceeds the size limit in bytes
+    TooLarge(u64),
+    Upload(String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for StoreError {
+    fn from(error: io::Error) -> Self {
+        StoreError::Io(error)
+    }
+}
+
+/// An uploaded blob written to the staging directory, it becomes visible with `commit`
+pub struct StagedBlob {
+    pub sha256: String,
+    pub size: i64,
+    path: PathBuf,
+}
+
+/// Blobs are stored once per content under `<root>/<first 2 hex digits>/<sha256>`
+pub struct ArtifactStore {
+    root: PathBuf,
+    max_size: u64,
+}
+
+impl ArtifactStore {
+    pub fn new(config: &ArtifactConfig) -> Self {
+        ArtifactStore {
+            root: PathBuf::from(&config.root),
+            max_size: config.max_size_bytes(),
+        }
+    }
+
+    pub fn path_of(&self, sha256: &str) -> PathBuf {
+        self.root.join(&sha256[..2]).join(sha256)
+    }
+
+    /// Write an upload to the staging directory while hashing it, it is dropped when it exceeds the size limit
+    pub async fn stage<S, E>(&self, mut body: S) -> Result<StagedBlob, StoreError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: std::fmt::Display,
+    {
+        let staging = self.root.join(STAGING_DIR);
+        fs::create_dir_all(&staging).await?;
+        let path = staging.join(format!(
+            "{}-{}",
+            std::process::id(),
+            STAGING_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let written = write_hashed(&path, &mut body, self.max_size).await;
+        match written {
+            Ok((sha256, size)) => Ok(StagedBlob { sha256, size: size as i64, path }),
+            Err(e) => {
+                let _ = fs::remove_file(&path).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Move a staged blob to its content path, an existing blob with the same content is replaced
+    pub async fn commit(&self, staged: &StagedBlob) -> io::Result<()> {
+        let path = self.path_of(&staged.sha256);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        fs::rename(&staged.path, &path).await
+    }
+
+    /// Remove a staged blob which wasn't committed, nothing is left to do after `commit`
+    pub async fn discard(&self, staged: StagedBlob) {
+        let _ = fs::remove_file(&staged.path).await;
+    }
+
+    async fn remove(&self, sha256: &str) -> io::Result<()> {
+        match fs::remove_file(self.path_of(sha256)).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove the blobs no attachment refers to, e.g. after the data retention deleted their test results.
+    ///
+    /// The rows are deleted first so that the transaction holds the write lock while the files are removed:
+    /// an upload of the same content waits and stores its file again.
+    pub async fn sweep(&self, pool: &SqlitePool) -> anyhow::Result<usize> {
+        let mut tx = pool.begin().await?;
+
+        let orphans = delete_orphan_blobs(&mut tx).await?;
+        for sha256 in &orphans {
+            self.remove(sha256).await?;
+        }
+
+        tx.commit().await?;
+        Ok(orphans.len())
+    }
+}
+
+async fn write_hashed<S, E>(path: &Path, body: &mut S, max_size: u64) -> Result<(String, u64), StoreError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let mut file = fs::File::create(path).await?;
+    let mut hasher = Sha256::new();
+    let mut size: u64 = 0;
+
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|e| StoreError::Upload(e.to_string()))?;
+        size += chunk.len() as u64;
+        if size > max_size {
+            return Err(StoreError::TooLarge(max_size));
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+
+    Ok((hex::encode(hasher.finalize()), size))
+}
@@ -1,18 +1,17 @@
 use axum::{
     async_trait,
     extract::{Request, State},
-    http::StatusCode,
     middleware::Next,
     response::Response,
 };
 use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm, TokenData};
 use std::{fs, sync::Arc};
 
-use crate::{models::Claims, state::AppState, config::Config};
+use crate::{error::AppError, models::Claims, state::AppState, config::Config};
 
 #[async_trait]
 pub trait JwtVerifier: Send + Sync {
-    async fn verify(&self, token: &str) -> Result<TokenData<Claims>, (StatusCode, String)>;
+    async fn verify(&self, token: &str) -> Result<TokenData<Claims>, AppError>;
 }
 
 pub struct AuthProvider {
@@ -57,9 +56,9 @@ impl AuthProvider {
 
 #[async_trait]
 impl JwtVerifier for AuthProvider {
-    async fn verify(&self, token: &str) -> Result<TokenData<Claims>, (StatusCode, String)> {
+    async fn verify(&self, token: &str) -> Result<TokenData<Claims>, AppError> {
         decode::<Claims>(token, &self.decoding_key, &self.validation)
-            .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid or expired token".into()))
+            .map_err(|_| AppError::Unauthorized("Invalid or expired token".into()))
     }
 }
 
@@ -71,7 +70,7 @@ pub async fn jwt_auth_middleware(
     State(state): State<Arc<AppState>>,
     request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, String)> {
+) -> Result<Response, AppError> {
     // If auth provider is None, bypass JWT validation
     let auth_provider = match &state.auth_provider {
         Some(provider) => provider,
@@ -83,17 +82,15 @@ pub async fn jwt_auth_middleware(
         .headers()
         .get("Authorization")
         .and_then(|v| v.to_str().ok())
-        .ok_or((StatusCode::UNAUTHORIZED, "Missing Authorization header".to_string()))?;
+        .ok_or(AppError::Unauthorized("Missing Authorization header".to_string()))?;
 
     if !auth_header.starts_with("Bearer ") {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid authorization header format".to_string()));
+        return Err(AppError::Unauthorized("Invalid authorization header format".to_string()));
     }
 
     let token = auth_header.trim_start_matches("Bearer ").trim();
 
     // Verify the token using the auth provider
-    match auth_provider.verify(token).await {
-        Ok(_token_data) => Ok(next.run(request).await),
-        Err((_status, error)) => Err((StatusCode::UNAUTHORIZED, error)),
-    }
+    auth_provider.verify(token).await?;
+    Ok(next.run(request).await)
 }
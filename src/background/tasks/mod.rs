@@ -1,8 +1,9 @@
 
 use std::sync::Arc;
-use crate::{background::{scheduler::ScheduledTask, tasks::sweeper::sqlite_clean_up_task}, state::AppState};
+use crate::{background::{scheduler::ScheduledTask, tasks::{sweeper::sqlite_clean_up_task, webhook::webhook_delivery_task}}, state::AppState};
 
 pub mod sweeper;
+pub mod webhook;
 
 
 /// Build scheduled tasks using the entire application state
@@ -13,6 +14,10 @@ pub fn create_tasks(state: Arc<AppState>) -> Vec<ScheduledTask<AppState>> {
         tasks.push(task);
     }
 
+    if let Some(task) = webhook_delivery_task(Arc::clone(&state)) {
+        tasks.push(task);
+    }
+
     // add more tasks here.
 
     tasks
@@ -2,7 +2,7 @@ use std::{sync::Arc, time::Instant};
 use anyhow::Result;
 use tokio::task::JoinHandle;
 
-use crate::{background::scheduler::{new_task, ScheduledTask}, config::DataRetentionConfig, database, state::AppState};
+use crate::{artifact::ArtifactStore, background::scheduler::{new_task, ScheduledTask}, config::DataRetentionConfig, database, state::AppState};
 
 #[async_trait::async_trait]
 trait Datasource: Send + Sync {
@@ -25,6 +25,14 @@ where
     println!("Clean up data done in {:.2?}", elapsed);
 }
 
+/// Remove the artifact blobs whose attachments were deleted with their test results
+async fn sweep_artifacts(pool: &sqlx::SqlitePool, store: &ArtifactStore) {
+    match store.sweep(pool).await {
+        Ok(removed) => println!("Removed {} unreferenced artifact blobs", removed),
+        Err(e) => eprintln!("Artifact sweep failed: {:?}", e),
+    }
+}
+
 /// Implement DatabasePool for SqlitePool to be used in scheduler
 #[async_trait::async_trait]
 impl Datasource for sqlx::SqlitePool {
@@ -44,9 +52,11 @@ pub fn sqlite_clean_up_task(state: Arc<AppState>) -> Option<ScheduledTask<AppSta
     Some(new_task(cfg_cloned.cron.clone(), move |state: Arc<AppState>| {
         let pool = state.pool.clone();
         let data_retention_cfg = cfg_cloned.clone();
+        let store = ArtifactStore::new(&state.config.artifact);
 
         tokio::spawn(async move {
             clean_up_data(&pool, &data_retention_cfg).await;
+            sweep_artifacts(&pool, &store).await;
         }) as JoinHandle<()>
     }))
 }
\ No newline at end of file
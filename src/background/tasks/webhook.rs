@@ -0,0 +1,38 @@
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+use crate::{background::scheduler::{new_task, ScheduledTask}, state::AppState, webhook::{deliver_due, PublicResolver}};
+
+/// Send the due webhook deliveries, disabled webhooks keep their deliveries pending
+pub fn webhook_delivery_task(state: Arc<AppState>) -> Option<ScheduledTask<AppState>> {
+    let cfg = state.config.webhook.clone();
+    if !cfg.enabled {
+        return None;
+    }
+
+    // redirects aren't followed, they could lead to an address the resolver doesn't see
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(cfg.timeout_in_sec))
+        .dns_resolver(Arc::new(PublicResolver::new(&cfg)))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Webhook client failed to build, deliveries are not sent: {:?}", e);
+            return None;
+        }
+    };
+
+    Some(new_task(cfg.cron.clone(), move |state: Arc<AppState>| {
+        let pool = state.pool.clone();
+        let client = client.clone();
+        let webhook_cfg = cfg.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = deliver_due(&pool, &client, &webhook_cfg).await {
+                eprintln!("Webhook delivery failed: {:?}", e);
+            }
+        }) as JoinHandle<()>
+    }))
+}
@@ -6,12 +6,14 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::watch;
 use tokio::time::{sleep, Instant};
 
 use crossbeam_queue::ArrayQueue;
+use parking_lot::Mutex;
 
 use crate::config::Config;
-use crate::database::upsert_test_result;
+use crate::database::{claim_idempotency_key, client_id_scope, upsert_test_result};
 use crate::models::CreateTestResult;
 
 // the interface for writer
@@ -43,6 +45,10 @@ pub trait Writer: Send + Sync + 'static {
             .map_err(|e| format!("Failed to enqueue message: {}", e))
     }
 
+    /// Wait until the messages of an execution enqueued so far are written,
+    /// the messages of other executions don't hold the wait back
+    async fn flush(&self, execution_id: i64, timeout: Duration) -> Result<(), String>;
+
     fn shutdown(&self) {
         drop(self.sender().clone());
     }
@@ -52,6 +58,13 @@ pub trait Writer: Send + Sync + 'static {
 #[derive(Clone)]
 pub struct DefaultWriter {
     sender: Sender<CreateTestResult>,
+    log_offload_threshold: usize,
+    client_id_ttl_seconds: i64,
+    // messages enqueued and not written yet by execution, and messages written so far by the writer task
+    pending: Arc<Mutex<HashMap<i64, u64>>>,
+    written: watch::Receiver<u64>,
+    // asks the writer task to write its buffer without waiting for the flush interval
+    flush_requested: Arc<AtomicBool>,
 }
 
 #[async_trait]
@@ -76,6 +89,8 @@ impl Writer for DefaultWriter {
 
         let batch_size = writer_config.batch_size;
         let flush_interval_ms = writer_config.flush_interval_ms;
+        let log_offload_threshold = config.log.offload_threshold_bytes();
+        let client_id_ttl_seconds = config.idempotency.ttl_seconds();
         let queue_capacity = (batch_size * 16).max(1024);
 
         let (tx, mut rx): (Sender<CreateTestResult>, Receiver<CreateTestResult>) =
@@ -83,6 +98,9 @@ impl Writer for DefaultWriter {
 
         let queue = Arc::new(ArrayQueue::<CreateTestResult>::new(queue_capacity));
         let closed = Arc::new(AtomicBool::new(false));
+        let flush_requested = Arc::new(AtomicBool::new(false));
+        let (written_tx, written_rx) = watch::channel(0u64);
+        let pending: Arc<Mutex<HashMap<i64, u64>>> = Arc::new(Mutex::new(HashMap::new()));
         let ds_clone_for_writer = ds.clone();
 
         // Dispatcher task: channel -> queue
@@ -108,6 +126,8 @@ impl Writer for DefaultWriter {
         {
             let queue = Arc::clone(&queue);
             let closed = Arc::clone(&closed);
+            let flush_requested = Arc::clone(&flush_requested);
+            let pending = Arc::clone(&pending);
             let batch_size = batch_size;
             let flush_interval = Duration::from_millis(flush_interval_ms);
 
@@ -128,10 +148,16 @@ impl Writer for DefaultWriter {
                     let time_elapsed = now.duration_since(last_flush);
 
                     // flush data
-                    if !buffer.is_empty() && (buffer.len() >= batch_size || time_elapsed >= flush_interval) {
-                        if let Err(e) = flush_to_sqlite(&ds_clone_for_writer, &buffer).await {
+                    if !buffer.is_empty()
+                        && (buffer.len() >= batch_size
+                            || time_elapsed >= flush_interval
+                            || flush_requested.swap(false, Ordering::SeqCst))
+                    {
+                        if let Err(e) = flush_to_sqlite(&ds_clone_for_writer, &buffer, log_offload_threshold, client_id_ttl_seconds).await {
                             eprintln!("Error flushing to sqlite: {}", e);
                         } else {
+                            release_pending(&pending, &buffer);
+                            written_tx.send_modify(|written| *written += buffer.len() as u64);
                             buffer.clear();
                             last_flush = Instant::now();
                         }
@@ -142,7 +168,7 @@ impl Writer for DefaultWriter {
                         while let Some(item) = queue.pop() {
                             buffer.push(item);
                             if buffer.len() >= batch_size {
-                                if let Err(e) = flush_to_sqlite(&ds_clone_for_writer, &buffer).await {
+                                if let Err(e) = flush_to_sqlite(&ds_clone_for_writer, &buffer, log_offload_threshold, client_id_ttl_seconds).await {
                                     eprintln!("Error flushing to sqlite at shutdown: {}", e);
                                 }
                                 buffer.clear();
@@ -150,7 +176,7 @@ impl Writer for DefaultWriter {
                         }
 
                         if !buffer.is_empty() {
-                            if let Err(e) = flush_to_sqlite(&ds_clone_for_writer, &buffer).await {
+                            if let Err(e) = flush_to_sqlite(&ds_clone_for_writer, &buffer, log_offload_threshold, client_id_ttl_seconds).await {
                                 eprintln!("Error flushing to sqlite at shutdown: {}", e);
                             }
                             buffer.clear();
@@ -167,7 +193,49 @@ impl Writer for DefaultWriter {
             });
         }
 
-        Self { sender: tx }
+        Self {
+            sender: tx,
+            log_offload_threshold,
+            client_id_ttl_seconds,
+            pending,
+            written: written_rx,
+            flush_requested,
+        }
+    }
+
+    async fn enqueue(&self, message: Self::Message) -> Result<(), String> {
+        // counted before it is sent, so that a flush never returns before a message the writer already took
+        let execution_id = message.execution_id;
+        *self.pending.lock().entry(execution_id).or_default() += 1;
+        self.sender
+            .send(message)
+            .await
+            .map_err(|e| {
+                release_pending(&self.pending, std::slice::from_ref(&e.0));
+                format!("Failed to enqueue message: {}", e)
+            })
+    }
+
+    async fn flush(&self, execution_id: i64, timeout: Duration) -> Result<(), String> {
+        let is_written = || !self.pending.lock().contains_key(&execution_id);
+        if is_written() {
+            return Ok(());
+        }
+
+        let mut written = self.written.clone();
+        self.flush_requested.store(true, Ordering::SeqCst);
+        tokio::time::timeout(timeout, written.wait_for(|_| {
+            // a message taken after the first request may still sit in the buffer
+            let done = is_written();
+            if !done {
+                self.flush_requested.store(true, Ordering::SeqCst);
+            }
+            done
+        }))
+            .await
+            .map_err(|_| "Timed out waiting for the writer to flush".to_string())?
+            .map(|_| ())
+            .map_err(|_| "Writer task has stopped".to_string())
     }
 
     async fn flush_db(
@@ -175,13 +243,28 @@ impl Writer for DefaultWriter {
         ds: &Self::Datasource,
         buffer: &[Self::Message],
     ) -> Result<(), Self::Error> {
-        flush_to_sqlite(ds, buffer).await
+        flush_to_sqlite(ds, buffer, self.log_offload_threshold, self.client_id_ttl_seconds).await
+    }
+}
+
+/// Count the messages of a buffer as written, an execution without pending messages is dropped
+fn release_pending(pending: &Mutex<HashMap<i64, u64>>, buffer: &[CreateTestResult]) {
+    let mut pending = pending.lock();
+    for item in buffer {
+        if let Some(count) = pending.get_mut(&item.execution_id) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(&item.execution_id);
+            }
+        }
     }
 }
 
 async fn flush_to_sqlite(
     ds: &Pool<Sqlite>,
     buffer: &[CreateTestResult],
+    log_offload_threshold: usize,
+    client_id_ttl_seconds: i64,
 ) -> Result<(), sqlx::Error> {
     if buffer.is_empty() {
         return Ok(());
@@ -191,7 +274,14 @@ async fn flush_to_sqlite(
     let mut tx: Transaction<'_, Sqlite> = conn.begin().await?;
 
     for item in buffer {
-        upsert_test_result(&mut tx, item).await?;
+        // The client_id is recorded with its test result, a failed flush keeps neither and the retry isn't skipped
+        if let Some(client_id) = &item.client_id {
+            let scope = client_id_scope(item.execution_id);
+            if !claim_idempotency_key(&mut tx, &scope, client_id, client_id_ttl_seconds, None).await? {
+                continue;
+            }
+        }
+        upsert_test_result(&mut tx, item, log_offload_threshold).await?;
     }
 
     tx.commit().await?;
@@ -212,6 +302,7 @@ pub struct WriterManager {
 #[async_trait]
 pub trait AnyWriter: Send + Sync {
     async fn enqueue_boxed(&self, message: Box<dyn std::any::Any + Send>) -> Result<(), String>;
+    async fn flush(&self, execution_id: i64, timeout: Duration) -> Result<(), String>;
     fn shutdown(&self);
 }
 
@@ -228,6 +319,10 @@ where
         self.enqueue(msg).await
     }
 
+    async fn flush(&self, execution_id: i64, timeout: Duration) -> Result<(), String> {
+        Writer::flush(&**self, execution_id, timeout).await
+    }
+
     fn shutdown(&self) {
         Writer::shutdown(&**self)
     }
@@ -256,6 +351,12 @@ impl WriterManager {
         writer.enqueue_boxed(message).await
     }
 
+    /// Wait until the messages of an execution enqueued to the writer before the call are in the database
+    pub async fn flush(&self, name: WriterName, execution_id: i64, timeout: Duration) -> Result<(), String> {
+        let writer = self.writers.get(&name).ok_or("Writer not found")?;
+        writer.flush(execution_id, timeout).await
+    }
+
     pub fn shutdown_all(&self) {
         for w in self.writers.values() {
             w.shutdown();
@@ -6,6 +6,8 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 
+use crate::models::CreateOwnershipRule;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub server: ServerConfig,
@@ -14,6 +16,18 @@ pub struct Config {
     pub auth: AuthConfig,
     pub data_retention: HashMap<String, DataRetentionConfig>,
     pub execution_suggest: ExecutionSuggestConfig,
+    #[serde(default)]
+    pub performance: PerformanceConfig,
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default)]
+    pub artifact: ArtifactConfig,
+    #[serde(default)]
+    pub log: LogConfig,
+    #[serde(default)]
+    pub ownership: OwnershipConfig,
+    #[serde(default)]
+    pub webhook: WebhookConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -68,6 +82,132 @@ pub struct ExecutionSuggestConfig {
     pub max_candidates: usize,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct PerformanceConfig {
+    #[serde(default = "default_baseline_window")]
+    pub baseline_window: i64,
+    #[serde(default = "default_baseline_min_samples")]
+    pub baseline_min_samples: usize,
+    #[serde(default = "default_regression_multiple")]
+    pub regression_multiple: f64,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            baseline_window: default_baseline_window(),
+            baseline_min_samples: default_baseline_min_samples(),
+            regression_multiple: default_regression_multiple(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct IdempotencyConfig {
+    #[serde(default = "default_idempotency_ttl_in_hour")]
+    pub ttl_in_hour: i64,
+    // a key still in progress after this long is taken over by a retry, its request is considered lost
+    #[serde(default = "default_idempotency_lease_in_sec")]
+    pub lease_in_sec: i64,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl_in_hour: default_idempotency_ttl_in_hour(),
+            lease_in_sec: default_idempotency_lease_in_sec(),
+        }
+    }
+}
+
+impl IdempotencyConfig {
+    pub fn ttl_seconds(&self) -> i64 {
+        self.ttl_in_hour * 3600
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ArtifactConfig {
+    #[serde(default = "default_artifact_root")]
+    pub root: String,
+    #[serde(default = "default_artifact_max_size_in_mb")]
+    pub max_size_in_mb: u64,
+}
+
+impl Default for ArtifactConfig {
+    fn default() -> Self {
+        Self {
+            root: default_artifact_root(),
+            max_size_in_mb: default_artifact_max_size_in_mb(),
+        }
+    }
+}
+
+impl ArtifactConfig {
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_in_mb * 1024 * 1024
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LogConfig {
+    #[serde(default = "default_log_offload_threshold_in_kb")]
+    pub offload_threshold_in_kb: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            offload_threshold_in_kb: default_log_offload_threshold_in_kb(),
+        }
+    }
+}
+
+impl LogConfig {
+    pub fn offload_threshold_bytes(&self) -> usize {
+        self.offload_threshold_in_kb * 1024
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct OwnershipConfig {
+    // checked in order before the rules added by the admin API
+    #[serde(default)]
+    pub rules: Vec<CreateOwnershipRule>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhookConfig {
+    #[serde(default = "default_webhook_enabled")]
+    pub enabled: bool,
+    // how often the due deliveries are sent
+    #[serde(default = "default_webhook_cron")]
+    pub cron: String,
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: i64,
+    // delay before the first retry, doubled after every failed attempt
+    #[serde(default = "default_webhook_backoff_in_sec")]
+    pub backoff_in_sec: i64,
+    #[serde(default = "default_webhook_timeout_in_sec")]
+    pub timeout_in_sec: u64,
+    // hosts which may be sent to although they are loopback or private addresses, e.g. an internal receiver
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_webhook_enabled(),
+            cron: default_webhook_cron(),
+            max_attempts: default_webhook_max_attempts(),
+            backoff_in_sec: default_webhook_backoff_in_sec(),
+            timeout_in_sec: default_webhook_timeout_in_sec(),
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
 fn default_execution_suggest_enabled() -> bool {
     true
 }
@@ -84,6 +224,58 @@ fn default_max_candidates() -> usize {
     5
 }
 
+fn default_baseline_window() -> i64 {
+    30
+}
+
+fn default_baseline_min_samples() -> usize {
+    3
+}
+
+fn default_regression_multiple() -> f64 {
+    2.0
+}
+
+fn default_idempotency_ttl_in_hour() -> i64 {
+    24
+}
+
+fn default_idempotency_lease_in_sec() -> i64 {
+    300
+}
+
+fn default_artifact_root() -> String {
+    "data/artifacts".to_string()
+}
+
+fn default_artifact_max_size_in_mb() -> u64 {
+    20
+}
+
+fn default_log_offload_threshold_in_kb() -> usize {
+    64
+}
+
+fn default_webhook_enabled() -> bool {
+    true
+}
+
+fn default_webhook_cron() -> String {
+    "*/10 * * * * *".to_string()
+}
+
+fn default_webhook_max_attempts() -> i64 {
+    5
+}
+
+fn default_webhook_backoff_in_sec() -> i64 {
+    30
+}
+
+fn default_webhook_timeout_in_sec() -> u64 {
+    10
+}
+
 fn default_wal() -> bool {
     true
 }
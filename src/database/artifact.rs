@@ -0,0 +1,77 @@
+// src/database/artifact.rs
+// Artifacts: attachments of test results and the blobs holding their content
+
+use sqlx::{Result, SqliteConnection};
+
+use crate::models::TestArtifact;
+
+/// An attachment about to be recorded, its blob is already staged
+pub struct NewArtifact<'a> {
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub content_type: &'a str,
+    pub sha256: &'a str,
+    pub size: i64,
+}
+
+/// Record an attachment of a test result and its blob, the blob row is shared by identical contents
+pub async fn insert_test_artifact(
+    conn: &mut SqliteConnection,
+    test_result_id: i64,
+    artifact: &NewArtifact<'_>,
+) -> Result<TestArtifact> {
+    sqlx::query(
+        r#"
+        INSERT INTO artifact_blob (sha256, size, time_created)
+        VALUES (?, ?, strftime('%s', 'now'))
+        ON CONFLICT(sha256) DO NOTHING
+        "#
+    )
+    .bind(artifact.sha256)
+    .bind(artifact.size)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query_as::<_, TestArtifact>(
+        r#"
+        INSERT INTO test_artifact (test_result_id, name, kind, content_type, sha256, size, time_created)
+        VALUES (?, ?, ?, ?, ?, ?, strftime('%s', 'now'))
+        RETURNING *
+        "#
+    )
+    .bind(test_result_id)
+    .bind(artifact.name)
+    .bind(artifact.kind)
+    .bind(artifact.content_type)
+    .bind(artifact.sha256)
+    .bind(artifact.size)
+    .fetch_one(conn)
+    .await
+}
+
+pub async fn list_test_artifacts(conn: &mut SqliteConnection, test_result_id: i64) -> Result<Vec<TestArtifact>> {
+    sqlx::query_as::<_, TestArtifact>("SELECT * FROM test_artifact WHERE test_result_id = ? ORDER BY id")
+        .bind(test_result_id)
+        .fetch_all(conn)
+        .await
+}
+
+pub async fn get_test_artifact(conn: &mut SqliteConnection, id: i64) -> Result<TestArtifact> {
+    sqlx::query_as::<_, TestArtifact>("SELECT * FROM test_artifact WHERE id = ?")
+        .bind(id)
+        .fetch_one(conn)
+        .await
+}
+
+/// Delete the blobs no attachment refers to anymore, returns their SHA-256 so that the files can be removed
+pub async fn delete_orphan_blobs(conn: &mut SqliteConnection) -> Result<Vec<String>> {
+    sqlx::query_scalar(
+        r#"
+        DELETE FROM artifact_blob
+        WHERE NOT EXISTS (SELECT 1 FROM test_artifact WHERE test_artifact.sha256 = artifact_blob.sha256)
+        RETURNING sha256
+        "#
+    )
+    .fetch_all(conn)
+    .await
+}
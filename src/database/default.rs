@@ -1,9 +1,54 @@
 // src/db.rs
 // Main database for test result 
 
-use sqlx::{sqlite::SqlitePool, sqlite::SqlitePoolOptions, Result, SqliteConnection};
+use std::borrow::Cow;
+use sqlx::{sqlite::SqlitePool, sqlite::SqlitePoolOptions, types::Json, Connection, Result, SqliteConnection};
 use crate::config::Config;
-use crate::models::{CreateTestResult, TestResult};
+use crate::database::log::{delete_offloaded_log, full_log, save_offloaded_log};
+use crate::database::step::replace_test_steps;
+use crate::database::{fill_missing_test_cases, upsert_test_case};
+use crate::database::summary::{apply_execution_stats, fill_missing_execution_stats, StatsEntry};
+use crate::models::{CreateExecution, CreateTestResult, Execution, Status, TestResult};
+use crate::signature::failure_signature;
+use crate::timestamp;
+
+// Columns added after the initial schema: (table, column, definition, expression filling existing rows).
+// They are also part of the CREATE TABLE statements, this only upgrades existing databases.
+const COLUMN_UPGRADES: &[(&str, &str, &str, Option<&str>)] = &[
+    ("test_result", "failure_signature", "TEXT", None),
+    ("test_result", "quarantined", "INTEGER NOT NULL DEFAULT 0", None),
+    ("execution", "labels", "TEXT", None),
+    ("test_result", "time_received", "INTEGER NOT NULL DEFAULT 0", Some("time_created")),
+    ("test_result", "log_size", "INTEGER", Some("LENGTH(CAST(log AS BLOB))")),
+    ("test_result", "test_case_id", "INTEGER", None),
+    ("execution", "time_finished", "INTEGER", None),
+    ("idempotency_key", "request_hash", "TEXT", None),
+    ("idempotency_key", "claimed_at", "INTEGER", None),
+    ("execution", "time_received", "INTEGER NOT NULL DEFAULT 0", Some("time_created")),
+    ("quarantine", "test_case_id", "INTEGER", None),
+];
+
+/// Columns of `TestResult`, listed instead of `*` so that queries choose whether to read the log
+pub const TEST_RESULT_COLUMNS: &str = "id, execution_id, name, platform, description, status, execution_time, counter, \
+    log, log_size, screenshot_id, created_by, time_created, time_received, failure_signature, quarantined, test_case_id";
+
+/// Columns of `TestResult` for listings, the log is left out
+pub const TEST_RESULT_LISTING_COLUMNS: &str = "id, execution_id, name, platform, description, status, execution_time, counter, \
+    NULL AS log, log_size, screenshot_id, created_by, time_created, time_received, failure_signature, quarantined, test_case_id";
+
+// Tables derived from test_result whose layout changed: (table, newest column).
+// An outdated table is dropped, the migration recreates it and it is rebuilt from test_result.
+const DERIVED_TABLE_REBUILDS: &[(&str, &str)] = &[
+    ("execution_stats", "quarantined"),
+];
+
+// Timestamp columns which could receive milliseconds before the input was normalized to seconds
+const TIMESTAMP_COLUMNS: &[(&str, &str)] = &[
+    ("execution", "time_created"),
+    ("test_result", "time_created"),
+    ("test_result", "time_received"),
+    ("quarantine", "expires_at"),
+];
 
 pub async fn init_db(config: &Config) -> Result<(SqlitePool, SqlitePool)> {
     let wal_enabled = config.database.wal;
@@ -43,28 +88,158 @@ pub async fn init_db(config: &Config) -> Result<(SqlitePool, SqlitePool)> {
         .connect(&config.database.url)
         .await?;
 
+    // Add new columns to existing tables before the migration creates indexes on them
+    upgrade_columns(&main_pool).await?;
+    drop_outdated_derived_tables(&main_pool).await?;
+
     // Run migrations on both pools
     sqlx::query(include_str!("../../migrations/cleopatra.sql"))
         .execute(&main_pool)
         .await?;
 
+    normalize_millisecond_timestamps(&main_pool).await?;
+
+    // Catalog the tests of results ingested before the catalog existed
+    fill_missing_test_cases(&main_pool).await?;
+
+    // Build execution stats for databases created before they were maintained
+    let has_stats: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM execution_stats)")
+        .fetch_one(&main_pool)
+        .await?;
+    if !has_stats {
+        let mut conn = main_pool.acquire().await?;
+        fill_missing_execution_stats(&mut conn).await?;
+    }
+
     Ok((main_pool, writer_pool))
 }
 
+/// Add the columns of `COLUMN_UPGRADES` which are missing in existing tables
+async fn upgrade_columns(pool: &SqlitePool) -> Result<()> {
+    for (table, column, definition, backfill) in COLUMN_UPGRADES {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(pool)
+            .await?;
+
+        // a missing table is created by the migration with all of its columns
+        if columns.is_empty() || columns.iter().any(|name| name == column) {
+            continue;
+        }
+
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(pool)
+            .await?;
+
+        // existing rows take the value of the expression instead of the default
+        if let Some(expr) = backfill {
+            sqlx::query(&format!("UPDATE {} SET {} = {}", table, column, expr))
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert the values of `TIMESTAMP_COLUMNS` stored in milliseconds to seconds, the data retention compares them with seconds
+async fn normalize_millisecond_timestamps(pool: &SqlitePool) -> Result<()> {
+    for (table, column) in TIMESTAMP_COLUMNS {
+        sqlx::query(&format!("UPDATE {} SET {} = {} / 1000 WHERE {} >= ?", table, column, column, column))
+            .bind(timestamp::MILLIS_THRESHOLD)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Drop the tables of `DERIVED_TABLE_REBUILDS` which miss their newest column
+async fn drop_outdated_derived_tables(pool: &SqlitePool) -> Result<()> {
+    for (table, column) in DERIVED_TABLE_REBUILDS {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(pool)
+            .await?;
+
+        if columns.is_empty() || columns.iter().any(|name| name == column) {
+            continue;
+        }
+
+        sqlx::query(&format!("DROP TABLE {}", table))
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Whether an active quarantine entry matches the test case, or the test on every platform.
+/// An entry created before the test was cataloged has no test case yet and matches by name and platform.
+async fn is_quarantined(
+    conn: &mut SqliteConnection,
+    test_case_id: i64,
+    name: &str,
+    platform: &str,
+) -> Result<bool> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM quarantine
+            WHERE (test_case_id = ? OR (test_case_id IS NULL AND name = ? AND (platform IS NULL OR platform = ?)))
+              AND (expires_at IS NULL OR expires_at > strftime('%s', 'now'))
+        )
+        "#
+    )
+    .bind(test_case_id)
+    .bind(name)
+    .bind(platform)
+    .fetch_one(conn)
+    .await
+}
+
+/// Signature of a failed test result, used to cluster failures sharing the same cause
+fn signature_of(status: &Status, log: Option<&str>) -> Option<String> {
+    if status.is_failure() {
+        log.and_then(failure_signature)
+    } else {
+        None
+    }
+}
+
 /// Upsert (insert or update) a test result.
 /// If a test result with the same (execution_id, name) exists, it will be updated and the counter incremented.
 /// Otherwise, a new test result will be created with counter set to 1.
+/// The test result is tagged as quarantined when an active quarantine entry matches it.
+/// The full-text index entry of the test result and the execution stats are refreshed as well.
+/// A log longer than `log_offload_threshold` bytes is stored compressed in test_log instead of test_result.
 pub async fn upsert_test_result(
     conn: &mut SqliteConnection,
     payload: &CreateTestResult,
+    log_offload_threshold: usize,
 ) -> Result<()> {
-    sqlx::query(
+    let previous = sqlx::query_as::<_, StatsEntry>(
+        "SELECT platform, status, execution_time, counter, quarantined FROM test_result WHERE execution_id = ? AND name = ?"
+    )
+    .bind(payload.execution_id)
+    .bind(&payload.name)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let test_case_id = upsert_test_case(conn, payload).await?;
+    let quarantined = is_quarantined(conn, test_case_id, &payload.name, &payload.platform).await?;
+
+    let offloaded = payload.log.as_deref().filter(|log| log.len() > log_offload_threshold);
+    let inline_log = if offloaded.is_some() { None } else { payload.log.as_deref() };
+
+    let (id, counter) = sqlx::query_as::<_, (i64, i64)>(
         r#"
         INSERT INTO test_result (
             execution_id, name, platform, description, status,
-            execution_time, counter, log, screenshot_id, created_by, time_created
+            execution_time, counter, log, log_size, screenshot_id, created_by, time_created,
+            time_received, failure_signature, quarantined, test_case_id
         )
-        VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?, ?, ?)
+        VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?, ?, ?, COALESCE(?, strftime('%s', 'now')), strftime('%s', 'now'), ?, ?, ?)
         ON CONFLICT(execution_id, name) DO UPDATE SET
             platform = excluded.platform,
             description = excluded.description,
@@ -72,7 +247,13 @@ pub async fn upsert_test_result(
             execution_time = excluded.execution_time,
             counter = test_result.counter + 1,
             log = excluded.log,
-            screenshot_id = excluded.screenshot_id
+            log_size = excluded.log_size,
+            screenshot_id = excluded.screenshot_id,
+            time_received = excluded.time_received,
+            failure_signature = excluded.failure_signature,
+            quarantined = excluded.quarantined,
+            test_case_id = excluded.test_case_id
+        RETURNING id, counter
         "#
     )
     .bind(payload.execution_id)
@@ -81,16 +262,101 @@ pub async fn upsert_test_result(
     .bind(payload.description.as_deref())
     .bind(&payload.status)
     .bind(payload.execution_time)
-    .bind(&payload.log)
-    .bind(&payload.screenshot_id)
-    .bind(&payload.created_by.as_deref())
+    .bind(inline_log)
+    .bind(payload.log.as_ref().map(|log| log.len() as i64))
+    .bind(payload.screenshot_id)
+    .bind(payload.created_by.as_deref())
     .bind(payload.time_created)
-    .execute(conn)
+    .bind(signature_of(&payload.status, payload.log.as_deref()))
+    .bind(quarantined)
+    .bind(test_case_id)
+    .fetch_one(&mut *conn)
     .await?;
 
+    match offloaded {
+        Some(log) => save_offloaded_log(conn, id, log).await?,
+        None => delete_offloaded_log(conn, id).await?,
+    }
+    replace_test_steps(conn, id, payload.steps.as_deref().unwrap_or_default()).await?;
+
+    // Replace the contribution of the previous run in the execution stats
+    if let Some(previous) = previous {
+        apply_execution_stats(conn, payload.execution_id, &previous, -1).await?;
+    }
+    let current = StatsEntry {
+        platform: payload.platform.clone(),
+        status: payload.status.clone(),
+        execution_time: payload.execution_time,
+        counter,
+        quarantined,
+    };
+    apply_execution_stats(conn, payload.execution_id, &current, 1).await?;
+
+    sync_test_result_fts(conn, id, payload, log_offload_threshold).await
+}
+
+/// Replace the full-text index entry of a test result.
+/// Offloaded logs are indexed by their head and tail only, so the index stays within the offload threshold per row.
+async fn sync_test_result_fts(
+    conn: &mut SqliteConnection,
+    id: i64,
+    payload: &CreateTestResult,
+    log_offload_threshold: usize,
+) -> Result<()> {
+    sqlx::query("DELETE FROM test_result_fts WHERE rowid = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("INSERT INTO test_result_fts (rowid, name, description, log) VALUES (?, ?, ?, ?)")
+        .bind(id)
+        .bind(&payload.name)
+        .bind(payload.description.as_deref())
+        .bind(payload.log.as_deref().map(|log| indexed_log(log, log_offload_threshold)))
+        .execute(&mut *conn)
+        .await?;
+
     Ok(())
 }
 
+/// The part of a log that goes into the full-text index: all of it up to `limit` bytes,
+/// otherwise its first and last `limit / 2` bytes where the test setup and the failure usually are
+fn indexed_log(log: &str, limit: usize) -> Cow<'_, str> {
+    if log.len() <= limit {
+        return Cow::Borrowed(log);
+    }
+    let mut head = limit / 2;
+    while !log.is_char_boundary(head) {
+        head -= 1;
+    }
+    let mut tail = log.len() - limit / 2;
+    while !log.is_char_boundary(tail) {
+        tail += 1;
+    }
+    Cow::Owned(format!("{}\n{}", &log[..head], &log[tail..]))
+}
+
+/// Create an execution, the server time is used when the client leaves `time_created` out
+pub async fn create_execution(
+    conn: &mut SqliteConnection,
+    payload: &CreateExecution,
+) -> Result<Execution> {
+    sqlx::query_as::<_, Execution>(
+        r#"
+        INSERT INTO execution (name, tag, created_by, time_created, labels, time_received)
+        VALUES (?, ?, ?, COALESCE(?, strftime('%s', 'now')), ?, strftime('%s', 'now'))
+        RETURNING *
+        "#
+    )
+    .bind(&payload.name)
+    .bind(&payload.tag)
+    .bind(&payload.created_by)
+    .bind(payload.time_created)
+    .bind(payload.labels.as_ref().map(Json))
+    .fetch_one(conn)
+    .await
+}
+
 /// Check if an execution exists by its ID
 pub async fn check_execution_existing(
     conn: &mut SqliteConnection,
@@ -109,24 +375,51 @@ pub async fn check_execution_existing(
 }
 
 /// Update the status of a test result by its ID
+/// The failure signature follows the status, it is only kept for failed test results.
+/// The execution stats are updated in the same transaction.
 pub async fn update_test_result_status(
     conn: &mut SqliteConnection,
     id: i64,
     status: &crate::models::Status,
 ) -> Result<TestResult> {
-    let test_result = sqlx::query_as::<_, TestResult>(
+    let mut tx = conn.begin().await?;
+
+    let (previous, log) = sqlx::query_as::<_, (String, Status, Option<i64>, i64, bool, Option<String>)>(
+        "SELECT platform, status, execution_time, counter, quarantined, log FROM test_result WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map(|(platform, status, execution_time, counter, quarantined, log)| {
+        (StatsEntry { platform, status, execution_time, counter, quarantined }, log)
+    })?;
+    let log = full_log(&mut tx, id, log).await?;
+
+    let test_result = sqlx::query_as::<_, TestResult>(&format!(
         r#"
         UPDATE test_result 
-        SET status = ?
+        SET status = ?, failure_signature = ?
         WHERE id = ?
-        RETURNING *
-        "#
-    )
+        RETURNING {}
+        "#,
+        TEST_RESULT_COLUMNS
+    ))
     .bind(status)
+    .bind(signature_of(status, log.as_deref()))
     .bind(id)
-    .fetch_one(conn)
+    .fetch_one(&mut *tx)
     .await?;
 
+    // Move the test result to the bucket of its new status
+    apply_execution_stats(&mut tx, test_result.execution_id, &previous, -1).await?;
+    let current = StatsEntry {
+        status: status.clone(),
+        ..previous
+    };
+    apply_execution_stats(&mut tx, test_result.execution_id, &current, 1).await?;
+
+    tx.commit().await?;
+
     Ok(test_result)
 }
 
@@ -136,7 +429,57 @@ pub async fn clean_up_db(pool: &SqlitePool, days: u32) -> anyhow::Result<()> {
     // Delete execution records older than specified days first
     sqlx::query(
         r#"DELETE FROM execution 
-           WHERE time_created < (strftime('%s', 'now', ?))"#
+           WHERE time_received < (strftime('%s', 'now', ?))"#
+    )
+    .bind(format!("-{} days", days))
+    .execute(&mut *tx)
+    .await?;
+
+    // Drop the stats of the executions losing test results, they are rebuilt below
+    sqlx::query(
+        r#"DELETE FROM execution_stats WHERE execution_id IN (
+               SELECT DISTINCT execution_id FROM test_result
+               WHERE time_received < (strftime('%s', 'now', ?)))"#
+    )
+    .bind(format!("-{} days", days))
+    .execute(&mut *tx)
+    .await?;
+
+    // Drop the full-text entries of the test results about to be removed
+    sqlx::query(
+        r#"DELETE FROM test_result_fts WHERE rowid IN (
+               SELECT id FROM test_result
+               WHERE time_received < (strftime('%s', 'now', ?)))"#
+    )
+    .bind(format!("-{} days", days))
+    .execute(&mut *tx)
+    .await?;
+
+    // Drop the attachments of the test results about to be removed, the sweeper removes their blobs
+    sqlx::query(
+        r#"DELETE FROM test_artifact WHERE test_result_id IN (
+               SELECT id FROM test_result
+               WHERE time_received < (strftime('%s', 'now', ?)))"#
+    )
+    .bind(format!("-{} days", days))
+    .execute(&mut *tx)
+    .await?;
+
+    // Drop the offloaded logs of the test results about to be removed
+    sqlx::query(
+        r#"DELETE FROM test_log WHERE test_result_id IN (
+               SELECT id FROM test_result
+               WHERE time_received < (strftime('%s', 'now', ?)))"#
+    )
+    .bind(format!("-{} days", days))
+    .execute(&mut *tx)
+    .await?;
+
+    // Drop the steps of the test results about to be removed
+    sqlx::query(
+        r#"DELETE FROM test_step WHERE test_result_id IN (
+               SELECT id FROM test_result
+               WHERE time_received < (strftime('%s', 'now', ?)))"#
     )
     .bind(format!("-{} days", days))
     .execute(&mut *tx)
@@ -145,7 +488,24 @@ pub async fn clean_up_db(pool: &SqlitePool, days: u32) -> anyhow::Result<()> {
     // Then delete test_result records older than specified days 
     sqlx::query(
         r#"DELETE FROM test_result 
-           WHERE time_created < (strftime('%s', 'now', ?))"#
+           WHERE time_received < (strftime('%s', 'now', ?))"#
+    )
+    .bind(format!("-{} days", days))
+    .execute(&mut *tx)
+    .await?;
+
+    // Rebuild the stats of the executions which still have test results
+    fill_missing_execution_stats(&mut tx).await?;
+
+    // Expired idempotency keys are only kept until the next clean up
+    sqlx::query("DELETE FROM idempotency_key WHERE expires_at <= strftime('%s', 'now')")
+        .execute(&mut *tx)
+        .await?;
+
+    // Webhook deliveries are kept as long as executions, unless they are still pending
+    sqlx::query(
+        r#"DELETE FROM webhook_delivery
+           WHERE status != 'pending' AND time_created < (strftime('%s', 'now', ?))"#
     )
     .bind(format!("-{} days", days))
     .execute(&mut *tx)
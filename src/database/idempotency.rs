@@ -0,0 +1,127 @@
+// src/database/idempotency.rs
+// Idempotency keys: requests and result lines handled once, replays get the stored response
+
+use sqlx::{Result, SqliteConnection};
+
+/// Stored state of an idempotency key which was already claimed
+pub enum StoredResponse {
+    /// The first request is still running
+    InProgress,
+    /// `request_hash` is None for the keys stored before request hashes were recorded
+    Done { status: u16, body: String, request_hash: Option<String> },
+}
+
+/// Claim a key for `ttl_seconds`, returns false when it is held by a request which hasn't expired.
+/// An expired key is claimed again. With a `lease_seconds`, a key still in progress after the lease
+/// is claimed again too, its request is considered lost (e.g. the server stopped while running it).
+pub async fn claim_idempotency_key(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    key: &str,
+    ttl_seconds: i64,
+    lease_seconds: Option<i64>,
+) -> Result<bool> {
+    let claimed = sqlx::query(
+        r#"
+        INSERT INTO idempotency_key (scope, key, expires_at, claimed_at)
+        VALUES (?, ?, strftime('%s', 'now') + ?, CASE WHEN ? IS NULL THEN NULL ELSE strftime('%s', 'now') END)
+        ON CONFLICT(scope, key) DO UPDATE SET
+            response_status = NULL,
+            response_body = NULL,
+            request_hash = NULL,
+            expires_at = excluded.expires_at,
+            claimed_at = excluded.claimed_at
+        WHERE idempotency_key.expires_at <= strftime('%s', 'now')
+           OR (idempotency_key.response_status IS NULL
+               AND idempotency_key.claimed_at <= strftime('%s', 'now') - ?)
+        "#,
+    )
+    .bind(scope)
+    .bind(key)
+    .bind(ttl_seconds)
+    .bind(lease_seconds)
+    .bind(lease_seconds)
+    .execute(conn)
+    .await?
+    .rows_affected();
+
+    Ok(claimed > 0)
+}
+
+/// Response stored with a key claimed by an earlier request
+pub async fn load_idempotent_response(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    key: &str,
+) -> Result<StoredResponse> {
+    let row = sqlx::query_as::<_, (Option<i64>, Option<String>, Option<String>)>(
+        "SELECT response_status, response_body, request_hash FROM idempotency_key WHERE scope = ? AND key = ?",
+    )
+    .bind(scope)
+    .bind(key)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(match row {
+        Some((Some(status), Some(body), request_hash)) => StoredResponse::Done { status: status as u16, body, request_hash },
+        _ => StoredResponse::InProgress,
+    })
+}
+
+/// Store the response of the request holding a key, with the hash of its body to check the replays against
+pub async fn save_idempotent_response(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    key: &str,
+    request_hash: &str,
+    status: u16,
+    body: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE idempotency_key SET request_hash = ?, response_status = ?, response_body = ? WHERE scope = ? AND key = ?",
+    )
+    .bind(request_hash)
+    .bind(status as i64)
+    .bind(body)
+    .bind(scope)
+    .bind(key)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether a key is held and not expired, e.g. the client_id of a test result already written
+pub async fn is_idempotency_key_claimed(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    key: &str,
+) -> Result<bool> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM idempotency_key WHERE scope = ? AND key = ? AND expires_at > strftime('%s', 'now'))",
+    )
+    .bind(scope)
+    .bind(key)
+    .fetch_one(conn)
+    .await
+}
+
+/// Scope of the client_id of the test results of an execution
+pub fn client_id_scope(execution_id: i64) -> String {
+    format!("execution:{}", execution_id)
+}
+
+/// Give a key back when its request failed, so that a retry runs again
+pub async fn release_idempotency_key(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    key: &str,
+) -> Result<()> {
+    sqlx::query("DELETE FROM idempotency_key WHERE scope = ? AND key = ?")
+        .bind(scope)
+        .bind(key)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
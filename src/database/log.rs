@@ -0,0 +1,72 @@
+// src/database/log.rs
+// Offloaded logs: logs above the threshold are stored compressed in test_log, test_result.log is NULL for them
+
+use sqlx::{Result, SqliteConnection};
+
+// Codec of the stored content, kept per row so that another codec can be added without migrating old rows
+const LOG_ENCODING: &str = "zstd";
+
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Store the log of a test result in test_log, replacing the log of a previous run
+pub async fn save_offloaded_log(conn: &mut SqliteConnection, test_result_id: i64, log: &str) -> Result<()> {
+    let content = zstd::encode_all(log.as_bytes(), COMPRESSION_LEVEL).map_err(sqlx::Error::Io)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO test_log (test_result_id, encoding, size, content)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(test_result_id) DO UPDATE SET
+            encoding = excluded.encoding,
+            size = excluded.size,
+            content = excluded.content
+        "#
+    )
+    .bind(test_result_id)
+    .bind(LOG_ENCODING)
+    .bind(log.len() as i64)
+    .bind(content)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Drop the offloaded log of a test result whose latest run has an inline log or none
+pub async fn delete_offloaded_log(conn: &mut SqliteConnection, test_result_id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM test_log WHERE test_result_id = ?")
+        .bind(test_result_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Load and decompress the offloaded log of a test result, None when its log is inline or missing
+pub async fn load_offloaded_log(conn: &mut SqliteConnection, test_result_id: i64) -> Result<Option<String>> {
+    let stored = sqlx::query_as::<_, (String, Vec<u8>)>("SELECT encoding, content FROM test_log WHERE test_result_id = ?")
+        .bind(test_result_id)
+        .fetch_optional(conn)
+        .await?;
+
+    stored
+        .map(|(encoding, content)| decode_log(&encoding, &content))
+        .transpose()
+        .map_err(|e| sqlx::Error::Decode(e.into()))
+}
+
+/// The log of a test result whichever way it is stored, `inline` is its test_result.log
+pub async fn full_log(conn: &mut SqliteConnection, test_result_id: i64, inline: Option<String>) -> Result<Option<String>> {
+    match inline {
+        Some(log) => Ok(Some(log)),
+        None => load_offloaded_log(conn, test_result_id).await,
+    }
+}
+
+fn decode_log(encoding: &str, content: &[u8]) -> std::result::Result<String, String> {
+    if encoding != LOG_ENCODING {
+        return Err(format!("unknown log encoding {}", encoding));
+    }
+    let bytes = zstd::decode_all(content).map_err(|e| format!("corrupt log: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
@@ -1,4 +1,18 @@
 // src/database/mod.rs
 
+pub mod artifact;
 pub mod default;
-pub use default::*;
\ No newline at end of file
+pub mod idempotency;
+pub mod log;
+pub mod step;
+pub mod summary;
+pub mod test_case;
+pub mod webhook;
+pub use artifact::*;
+pub use default::*;
+pub use idempotency::*;
+pub use log::*;
+pub use step::*;
+pub use summary::*;
+pub use test_case::*;
+pub use webhook::*;
@@ -0,0 +1,72 @@
+// src/database/step.rs
+// Steps within a test result, stored apart so that listings don't carry them
+
+use sqlx::{FromRow, QueryBuilder, Result, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+
+use crate::models::TestStep;
+
+#[derive(FromRow)]
+struct TestStepRow {
+    test_result_id: i64,
+    #[sqlx(flatten)]
+    step: TestStep,
+}
+
+/// Replace the steps of a test result with the steps of its latest run
+pub async fn replace_test_steps(conn: &mut SqliteConnection, test_result_id: i64, steps: &[TestStep]) -> Result<()> {
+    sqlx::query("DELETE FROM test_step WHERE test_result_id = ?")
+        .bind(test_result_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (position, step) in steps.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO test_step (test_result_id, position, name, status, duration, message)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(test_result_id)
+        .bind(position as i64)
+        .bind(&step.name)
+        .bind(&step.status)
+        .bind(step.duration)
+        .bind(step.message.as_deref())
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn list_test_steps(conn: &mut SqliteConnection, test_result_id: i64) -> Result<Vec<TestStep>> {
+    sqlx::query_as::<_, TestStep>(
+        "SELECT name, status, duration, message FROM test_step WHERE test_result_id = ? ORDER BY position"
+    )
+    .bind(test_result_id)
+    .fetch_all(conn)
+    .await
+}
+
+/// Steps of several test results with one query, by test result id
+pub async fn list_steps_of_results(conn: &mut SqliteConnection, test_result_ids: &[i64]) -> Result<HashMap<i64, Vec<TestStep>>> {
+    let mut steps: HashMap<i64, Vec<TestStep>> = HashMap::new();
+    if test_result_ids.is_empty() {
+        return Ok(steps);
+    }
+
+    let mut query = QueryBuilder::<Sqlite>::new(
+        "SELECT test_result_id, name, status, duration, message FROM test_step WHERE test_result_id IN ("
+    );
+    let mut separated = query.separated(", ");
+    for test_result_id in test_result_ids {
+        separated.push_bind(*test_result_id);
+    }
+    separated.push_unseparated(") ORDER BY test_result_id, position");
+
+    for row in query.build_query_as::<TestStepRow>().fetch_all(conn).await? {
+        steps.entry(row.test_result_id).or_default().push(row.step);
+    }
+    Ok(steps)
+}
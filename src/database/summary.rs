@@ -0,0 +1,263 @@
+// src/database/summary.rs
+// Execution summary: counters materialized in execution_stats
+
+use std::collections::HashMap;
+
+use sqlx::{FromRow, QueryBuilder, Result, Sqlite, SqliteConnection};
+
+use crate::models::{PlatformSummary, SlowTest, Status, Summary};
+
+// Number of slowest tests listed in the execution summary
+const SLOWEST_TESTS_IN_SUMMARY: i64 = 5;
+
+/// Aggregated counters of one platform and quarantine tag within an execution
+#[derive(FromRow)]
+struct PlatformRow {
+    execution_id: i64,
+    platform: String,
+    quarantined: bool,
+    total: i64,
+    pass: i64,
+    fail: i64,
+    ignor: i64,
+    error: i64,
+    skip: i64,
+    block: i64,
+    known: i64,
+    total_duration: i64,
+    timed: i64,
+    retried: i64,
+    flaky: i64,
+}
+
+/// The fields of a test result which count in the execution stats
+#[derive(FromRow)]
+pub struct StatsEntry {
+    pub platform: String,
+    pub status: Status,
+    pub execution_time: Option<i64>,
+    pub counter: i64,
+    pub quarantined: bool,
+}
+
+#[derive(FromRow)]
+struct SlowTestRow {
+    execution_id: i64,
+    #[sqlx(flatten)]
+    test: SlowTest,
+}
+
+/// Passed over executed tests, ignored, skipped, blocked and known failures don't count
+fn pass_rate(pass: i64, fail: i64, error: i64) -> f64 {
+    let executed = pass + fail + error;
+    if executed == 0 {
+        0.0
+    } else {
+        pass as f64 / executed as f64
+    }
+}
+
+fn push_execution_ids(builder: &mut QueryBuilder<'_, Sqlite>, execution_ids: &[i64]) {
+    let mut separated = builder.separated(", ");
+    for execution_id in execution_ids {
+        separated.push_bind(*execution_id);
+    }
+    separated.push_unseparated(")");
+}
+
+/// Load the summaries of several executions from execution_stats.
+/// Executions without any test result get an empty summary.
+/// Quarantined test results are only counted with `include_quarantined`, the number of them is always reported.
+pub async fn load_summaries(
+    conn: &mut SqliteConnection,
+    execution_ids: &[i64],
+    include_quarantined: bool,
+) -> Result<HashMap<i64, Summary>> {
+    let mut summaries: HashMap<i64, Summary> = execution_ids
+        .iter()
+        .map(|id| (*id, Summary::default()))
+        .collect();
+    if execution_ids.is_empty() {
+        return Ok(summaries);
+    }
+
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        r#"
+        SELECT execution_id, platform, quarantined, total, pass, fail, ignor,
+               error, skip, block, known,
+               total_duration, timed, retried, flaky
+        FROM execution_stats
+        WHERE total > 0 AND execution_id IN ("#,
+    );
+    push_execution_ids(&mut builder, execution_ids);
+    builder.push(" ORDER BY execution_id, platform, quarantined");
+
+    let rows = builder.build_query_as::<PlatformRow>().fetch_all(&mut *conn).await?;
+
+    let mut timed: HashMap<i64, i64> = HashMap::new();
+    for row in rows {
+        let summary = summaries.entry(row.execution_id).or_default();
+        if row.quarantined {
+            summary.quarantined += row.total;
+            if !include_quarantined {
+                continue;
+            }
+        }
+
+        summary.total += row.total;
+        summary.pass += row.pass;
+        summary.fail += row.fail;
+        summary.ignor += row.ignor;
+        summary.error += row.error;
+        summary.skip += row.skip;
+        summary.block += row.block;
+        summary.known += row.known;
+        summary.total_duration += row.total_duration;
+        summary.retried += row.retried;
+        summary.flaky += row.flaky;
+        *timed.entry(row.execution_id).or_default() += row.timed;
+
+        // rows are ordered by platform, the quarantined row of a platform follows the other one
+        let platform = match summary.platforms.last_mut() {
+            Some(platform) if platform.platform == row.platform => platform,
+            _ => {
+                summary.platforms.push(PlatformSummary {
+                    platform: row.platform,
+                    ..Default::default()
+                });
+                summary.platforms.last_mut().unwrap()
+            }
+        };
+        platform.total += row.total;
+        platform.pass += row.pass;
+        platform.fail += row.fail;
+        platform.ignor += row.ignor;
+        platform.error += row.error;
+        platform.skip += row.skip;
+        platform.block += row.block;
+        platform.known += row.known;
+        platform.total_duration += row.total_duration;
+    }
+
+    for (execution_id, summary) in summaries.iter_mut() {
+        for platform in summary.platforms.iter_mut() {
+            platform.pass_rate = pass_rate(platform.pass, platform.fail, platform.error);
+        }
+        summary.pass_rate = pass_rate(summary.pass, summary.fail, summary.error);
+        let timed = timed.get(execution_id).copied().unwrap_or(0);
+        if timed > 0 {
+            summary.avg_duration = summary.total_duration as f64 / timed as f64;
+        }
+    }
+
+    // the slowest tests of every execution
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        r#"
+        SELECT execution_id, id, name, platform, execution_time FROM (
+            SELECT execution_id, id, name, platform, execution_time,
+                   ROW_NUMBER() OVER (PARTITION BY execution_id ORDER BY execution_time DESC, id) AS rn
+            FROM test_result
+            WHERE execution_time IS NOT NULL AND (quarantined = 0 OR "#,
+    );
+    builder.push_bind(include_quarantined);
+    builder.push(") AND execution_id IN (");
+    push_execution_ids(&mut builder, execution_ids);
+    builder.push(") WHERE rn <= ");
+    builder.push_bind(SLOWEST_TESTS_IN_SUMMARY);
+    builder.push(" ORDER BY execution_id, rn");
+
+    let rows = builder.build_query_as::<SlowTestRow>().fetch_all(&mut *conn).await?;
+    for row in rows {
+        summaries.entry(row.execution_id).or_default().slowest.push(row.test);
+    }
+
+    Ok(summaries)
+}
+
+/// Add (`sign` = 1) or remove (`sign` = -1) the contribution of a test result to the stats of its execution
+pub async fn apply_execution_stats(
+    conn: &mut SqliteConnection,
+    execution_id: i64,
+    entry: &StatsEntry,
+    sign: i64,
+) -> Result<()> {
+    let passed = entry.status == Status::P;
+    let retried = entry.counter > 1;
+    let count = |status: Status| sign * (entry.status == status) as i64;
+
+    sqlx::query(
+        r#"
+        INSERT INTO execution_stats (
+            execution_id, platform, quarantined, total, pass, fail, ignor,
+            error, skip, block, known,
+            total_duration, timed, retried, flaky
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(execution_id, platform, quarantined) DO UPDATE SET
+            total = total + excluded.total,
+            pass = pass + excluded.pass,
+            fail = fail + excluded.fail,
+            ignor = ignor + excluded.ignor,
+            error = error + excluded.error,
+            skip = skip + excluded.skip,
+            block = block + excluded.block,
+            known = known + excluded.known,
+            total_duration = total_duration + excluded.total_duration,
+            timed = timed + excluded.timed,
+            retried = retried + excluded.retried,
+            flaky = flaky + excluded.flaky
+        "#
+    )
+    .bind(execution_id)
+    .bind(&entry.platform)
+    .bind(entry.quarantined)
+    .bind(sign)
+    .bind(count(Status::P))
+    .bind(count(Status::F))
+    .bind(count(Status::I))
+    .bind(count(Status::E))
+    .bind(count(Status::S))
+    .bind(count(Status::B))
+    .bind(count(Status::K))
+    .bind(sign * entry.execution_time.unwrap_or(0))
+    .bind(sign * entry.execution_time.is_some() as i64)
+    .bind(sign * retried as i64)
+    .bind(sign * (retried && passed) as i64)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Build the stats of the executions which have test results but no stats yet
+pub async fn fill_missing_execution_stats(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO execution_stats (
+            execution_id, platform, quarantined, total, pass, fail, ignor,
+            error, skip, block, known,
+            total_duration, timed, retried, flaky
+        )
+        SELECT execution_id, platform, quarantined,
+               COUNT(*),
+               SUM(status = 'P'),
+               SUM(status = 'F'),
+               SUM(status = 'I'),
+               SUM(status = 'E'),
+               SUM(status = 'S'),
+               SUM(status = 'B'),
+               SUM(status = 'K'),
+               COALESCE(SUM(execution_time), 0),
+               COUNT(execution_time),
+               SUM(counter > 1),
+               SUM(counter > 1 AND status = 'P')
+        FROM test_result
+        WHERE execution_id NOT IN (SELECT execution_id FROM execution_stats)
+        GROUP BY execution_id, platform, quarantined
+        "#
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+// src/database/test_case.rs
+// Test case catalog: a stable id per test name and platform across executions
+
+use sqlx::{Result, SqliteConnection, SqlitePool};
+
+use crate::models::CreateTestResult;
+
+/// Add the test of a result to the catalog on first sight, returns the id of its entry.
+/// A description already in the catalog is kept, a reported suite path replaces the previous one.
+pub async fn upsert_test_case(conn: &mut SqliteConnection, payload: &CreateTestResult) -> Result<i64> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO test_case (name, platform, description, suite_path, time_created, last_seen)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'), strftime('%s', 'now'))
+        ON CONFLICT(name, platform) DO UPDATE SET
+            description = COALESCE(test_case.description, excluded.description),
+            suite_path = COALESCE(excluded.suite_path, test_case.suite_path),
+            last_seen = excluded.last_seen
+        RETURNING id
+        "#
+    )
+    .bind(&payload.name)
+    .bind(&payload.platform)
+    .bind(payload.description.as_deref())
+    .bind(payload.suite_path.as_deref())
+    .fetch_one(conn)
+    .await
+}
+
+/// Catalog the tests of the results without entry and link the results and the quarantine entries to it
+pub async fn fill_missing_test_cases(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO test_case (name, platform, description, time_created, last_seen)
+        SELECT name, platform, MAX(description), MIN(time_received), MAX(time_received)
+        FROM test_result
+        WHERE test_case_id IS NULL
+        GROUP BY name, platform
+        ON CONFLICT(name, platform) DO NOTHING
+        "#
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE test_result SET test_case_id = (
+            SELECT id FROM test_case
+            WHERE test_case.name = test_result.name AND test_case.platform = test_result.platform
+        )
+        WHERE test_case_id IS NULL
+        "#
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // an entry without platform matches every platform and stays keyed by name
+    sqlx::query(
+        r#"
+        UPDATE quarantine SET test_case_id = (
+            SELECT id FROM test_case
+            WHERE test_case.name = quarantine.name AND test_case.platform = quarantine.platform
+        )
+        WHERE test_case_id IS NULL AND platform IS NOT NULL
+        "#
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
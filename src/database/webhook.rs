@@ -0,0 +1,118 @@
+// src/database/webhook.rs
+// Webhook deliveries: queued with the events, then claimed and retried by the background task
+
+use sqlx::{Result, SqliteConnection, SqlitePool};
+
+use crate::models::{FailedTest, WebhookDelivery};
+
+/// Queue an event for a webhook, the background task sends it on its next run
+pub async fn insert_webhook_delivery(
+    conn: &mut SqliteConnection,
+    webhook_id: i64,
+    event: &str,
+    payload: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO webhook_delivery (webhook_id, event, payload, status, next_attempt_at, time_created)
+        VALUES (?, ?, ?, 'pending', strftime('%s', 'now'), strftime('%s', 'now'))
+        "#
+    )
+    .bind(webhook_id)
+    .bind(event)
+    .bind(payload)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Take the due deliveries by moving their next attempt to `lease_in_sec` from now,
+/// so that an overlapping run of the task doesn't send them twice
+pub async fn claim_due_deliveries(pool: &SqlitePool, lease_in_sec: i64, limit: i64) -> Result<Vec<WebhookDelivery>> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        UPDATE webhook_delivery SET next_attempt_at = strftime('%s', 'now') + ?
+        WHERE id IN (
+            SELECT id FROM webhook_delivery
+            WHERE status = 'pending' AND next_attempt_at <= strftime('%s', 'now')
+            ORDER BY next_attempt_at, id
+            LIMIT ?
+        )
+        RETURNING *
+        "#
+    )
+    .bind(lease_in_sec)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_delivered(pool: &SqlitePool, id: i64, http_status: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE webhook_delivery
+        SET status = 'delivered', attempts = attempts + 1, last_status = ?, last_error = NULL,
+            time_delivered = strftime('%s', 'now')
+        WHERE id = ?
+        "#
+    )
+    .bind(http_status)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Record a failed attempt: the next one is due after `backoff_in_sec` doubled by the previous attempts,
+/// the delivery gives up after `max_attempts`
+pub async fn mark_attempt_failed(
+    pool: &SqlitePool,
+    id: i64,
+    http_status: Option<i64>,
+    error: &str,
+    backoff_in_sec: i64,
+    max_attempts: i64,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE webhook_delivery
+        SET attempts = attempts + 1, last_status = ?, last_error = ?,
+            status = CASE WHEN attempts + 1 >= ? THEN 'failed' ELSE 'pending' END,
+            next_attempt_at = strftime('%s', 'now') + (? << MIN(attempts, 20))
+        WHERE id = ?
+        "#
+    )
+    .bind(http_status)
+    .bind(error)
+    .bind(max_attempts)
+    .bind(backoff_in_sec)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The failed tests of an execution whose previous run, in an earlier execution, didn't fail.
+/// A test failing on its first run is a new failure too, quarantined tests are left out.
+pub async fn load_new_failures(conn: &mut SqliteConnection, execution_id: i64) -> Result<Vec<FailedTest>> {
+    sqlx::query_as::<_, FailedTest>(
+        r#"
+        SELECT r.id, r.name, r.platform, r.status, r.test_case_id
+        FROM test_result r
+        WHERE r.execution_id = ? AND r.status IN ('F', 'E') AND r.quarantined = 0
+          AND COALESCE((
+              SELECT p.status IN ('F', 'E') FROM test_result p
+              WHERE p.test_case_id = r.test_case_id AND p.execution_id < r.execution_id
+              ORDER BY p.execution_id DESC
+              LIMIT 1
+          ), 0) = 0
+        ORDER BY r.id
+        "#
+    )
+    .bind(execution_id)
+    .fetch_all(conn)
+    .await
+}
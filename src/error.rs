@@ -1,15 +1,197 @@
 use axum::{
     Json,
     body::{Body, Bytes, to_bytes},
-    http::{Request, StatusCode},
+    http::{header, HeaderValue, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use futures::FutureExt;
+use sqlx::error::ErrorKind;
+use std::fmt;
 
 use crate::models::ErrorResponse;
 
-/// Middleware to catch panics and error responses, converting to `ErrorResponse`.
+/// Marks the error responses whose body already is an `ErrorResponse`, the middleware passes them through
+#[derive(Clone, Copy)]
+struct TypedError;
+
+/// Invalid request payload, answered with 400 and the offending field
+#[derive(Debug)]
+pub struct ValidationError {
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        ValidationError {
+            field: Some(field.into()),
+            message: message.into(),
+        }
+    }
+
+    /// The same error on a field of a nested object
+    pub fn within(mut self, parent: &str) -> Self {
+        self.field = Some(match self.field {
+            Some(field) => format!("{}.{}", parent, field),
+            None => parent.to_string(),
+        });
+        self
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}: {}", field, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl IntoResponse for ValidationError {
+    fn into_response(self) -> Response {
+        AppError::Validation(self).into_response()
+    }
+}
+
+// Seconds a client should wait before retrying an overloaded request
+const RETRY_AFTER_SECONDS: &str = "1";
+
+/// Error of a request handler, answered as `ErrorResponse` with the matching status
+#[derive(Debug)]
+pub enum AppError {
+    Validation(ValidationError),
+    BadRequest(String),
+    NotFound(String),
+    Conflict(String),
+    /// The request is well-formed but can't be applied, e.g. an Idempotency-Key reused with another body
+    Unprocessable(String),
+    Unauthorized(String),
+    PayloadTooLarge(String),
+    /// The requested range starts at or past the end of a content of this many bytes
+    RangeNotSatisfiable(usize),
+    /// The request can't be taken now, e.g. the database is busy or the writer is shutting down
+    Overloaded(String),
+    Database(sqlx::Error),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn internal(error: impl fmt::Display) -> Self {
+        AppError::Internal(error.to_string())
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::Validation(_) | AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+            AppError::Overloaded(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AppError::Validation(_) => "VALIDATION_ERROR",
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Unprocessable(_) => "UNPROCESSABLE",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+            AppError::RangeNotSatisfiable(_) => "RANGE_NOT_SATISFIABLE",
+            AppError::Overloaded(_) => "OVERLOADED",
+            AppError::Database(_) | AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Validation(e) => write!(f, "{}", e),
+            AppError::Database(e) => write!(f, "{}", e),
+            AppError::RangeNotSatisfiable(size) => write!(f, "The range starts past the end of the {} bytes", size),
+            AppError::BadRequest(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::Unprocessable(message)
+            | AppError::Unauthorized(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::Overloaded(message)
+            | AppError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Database errors the client can act on get their own status: missing rows, constraints and a busy database
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => AppError::NotFound("Not found".to_string()),
+            sqlx::Error::PoolTimedOut => AppError::Overloaded("The database is busy, retry later".to_string()),
+            sqlx::Error::Database(db_error) => match db_error.kind() {
+                ErrorKind::UniqueViolation | ErrorKind::ForeignKeyViolation => AppError::Conflict(db_error.message().to_string()),
+                ErrorKind::NotNullViolation | ErrorKind::CheckViolation => AppError::BadRequest(db_error.message().to_string()),
+                _ if is_busy(db_error.code().as_deref()) => AppError::Overloaded("The database is busy, retry later".to_string()),
+                _ => AppError::Database(error),
+            },
+            _ => AppError::Database(error),
+        }
+    }
+}
+
+impl From<ValidationError> for AppError {
+    fn from(error: ValidationError) -> Self {
+        AppError::Validation(error)
+    }
+}
+
+/// SQLITE_BUSY and SQLITE_LOCKED, including their extended codes
+fn is_busy(code: Option<&str>) -> bool {
+    code.and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let error = self.code();
+        let overloaded = matches!(self, AppError::Overloaded(_));
+        let range_size = match self {
+            AppError::RangeNotSatisfiable(size) => Some(size),
+            _ => None,
+        };
+
+        let (message, field) = match self {
+            AppError::Validation(e) => (e.message, e.field),
+            other => (other.to_string(), None),
+        };
+        let body = ErrorResponse {
+            error: error.into(),
+            message,
+            field,
+        };
+
+        let mut response = (status, Json(body)).into_response();
+        if overloaded {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from_static(RETRY_AFTER_SECONDS));
+        }
+        if let Some(value) = range_size.and_then(|size| HeaderValue::try_from(format!("bytes */{}", size)).ok()) {
+            response.headers_mut().insert(header::CONTENT_RANGE, value);
+        }
+        response.extensions_mut().insert(TypedError);
+        response
+    }
+}
+
+/// Middleware to catch panics, and to convert the error responses built outside the handlers (e.g. rejections) to `ErrorResponse`.
 pub async fn handle_unexpected_errors(req: Request<Body>, next: Next) -> Response {
     // Catch panics
     let result = std::panic::AssertUnwindSafe(next.run(req))
@@ -28,6 +210,10 @@ pub async fn handle_unexpected_errors(req: Request<Body>, next: Next) -> Respons
         }
     };
 
+    if response.extensions().get::<TypedError>().is_some() {
+        return response;
+    }
+
     // Normalize error responses
     if response.status().is_client_error() || response.status().is_server_error() {
         let status = response.status();
@@ -2,5 +2,6 @@
 // Library crate for the Cleopatra application exposed to tests
 
 pub mod models;
+pub mod timestamp;
 
 pub use models::{Execution, TestResult, CreateTestResultResponse, StreamResponse, ExecutionListResponse, Status, SuggestedItem};
\ No newline at end of file
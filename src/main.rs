@@ -14,6 +14,13 @@ mod state;
 mod auth;
 mod error;
 mod suggestion;
+mod signature;
+mod validation;
+mod timestamp;
+mod artifact;
+mod time_format;
+mod ownership;
+mod webhook;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -28,6 +35,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Build app with routers and middleware
     let app = Router::new()
         .merge(routes::routes(&state_arc))
+        .layer(axum::middleware::from_fn(time_format::format_timestamps))
         .layer(axum::middleware::from_fn_with_state(
             Arc::clone(&state_arc),
             auth::jwt_auth_middleware, // expects State<Arc<AppState>>
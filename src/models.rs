@@ -1,10 +1,13 @@
 // src/models.rs
 // Define models here
 
+use std::collections::HashMap;
 use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{types::Json, FromRow};
+
+use crate::timestamp;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Execution {
@@ -13,6 +16,15 @@ pub struct Execution {
     pub tag: Option<String>,
     pub created_by: Option<String>,
     pub time_created: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Json<HashMap<String, String>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_finished: Option<i64>, // set by POST /api/execution/{id}/finish
+    #[serde(default)]
+    pub time_received: i64, // server time of the creation
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>, // only filled when include_summary=true
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -25,10 +37,34 @@ pub struct TestResult {
     pub status: Status,
     pub execution_time: Option<i64>,
     pub counter: i64,
-    pub log: Option<String>,
+    pub log: Option<String>, // left out of listings
+    #[serde(default)]
+    pub log_size: Option<i64>, // bytes of the log, also set when the log is left out
     pub screenshot_id: Option<i64>,
     pub created_by: Option<String>,
     pub time_created: i64,
+    #[serde(default)]
+    pub time_received: i64, // server time of the latest ingestion
+    pub failure_signature: Option<String>,
+    #[serde(default)]
+    pub quarantined: bool, // an active quarantine entry matched on ingestion
+    #[serde(default)]
+    pub test_case_id: Option<i64>, // the catalog entry of the test
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>, // resolved by the ownership rules when the result is read
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<TestStep>>, // only filled by GET /api/result/{id}
+}
+
+/// Outcome of one step within a test, e.g. a BDD step or a sub-assertion
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TestStep {
+    pub name: String,
+    pub status: Status,
+    pub duration: Option<i64>, // milliseconds, like execution_time
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,15 +72,26 @@ pub struct CreateExecution {
     pub name: String,
     pub tag: Option<String>,
     pub created_by: Option<String>,
-    pub time_created: i64,
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub time_created: Option<i64>, // the server time when omitted
+    pub labels: Option<HashMap<String, String>>,
+}
+
+/// First line of an NDJSON stream which creates its execution
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamHeader {
+    pub execution: CreateExecution,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExecutionListResponse {
-    pub total: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>, // only counted when include_total=true, the default in offset mode
     pub limit: i64,
     pub offset: i64,
     pub has_next: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>, // pass as cursor to get the next page
     pub items: Vec<Execution>,
 }
 
@@ -52,19 +99,68 @@ pub struct ExecutionListResponse {
 pub struct ExecutionResultsResponse {
     pub execution_id: i64,
     pub summary: Option<Summary>,
-    pub total: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>, // only counted when include_total=true, the default in offset mode
     pub limit: i64,
     pub offset: i64,
     pub has_next: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>, // pass as cursor to get the next page
     pub items: Vec<TestResult>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Summary {
     pub total: i64,
     pub pass: i64,
     pub fail: i64,
     pub ignor: i64,
+    pub error: i64,
+    pub skip: i64,
+    pub block: i64,
+    pub known: i64,
+    pub pass_rate: f64, // pass / (pass + fail + error)
+    pub total_duration: i64, // sum of execution_time
+    pub avg_duration: f64, // average over the tests reporting execution_time
+    pub retried: i64, // tests run more than once (counter > 1)
+    pub flaky: i64, // retried tests which passed eventually
+    pub quarantined: i64, // quarantined tests, only counted above with include_quarantined=true
+    pub platforms: Vec<PlatformSummary>,
+    pub slowest: Vec<SlowTest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    pub execution_id: i64,
+    pub summary: Summary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecutionSummariesResponse {
+    pub items: Vec<ExecutionSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PlatformSummary {
+    pub platform: String,
+    pub total: i64,
+    pub pass: i64,
+    pub fail: i64,
+    pub ignor: i64,
+    pub error: i64,
+    pub skip: i64,
+    pub block: i64,
+    pub known: i64,
+    pub pass_rate: f64,
+    pub total_duration: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SlowTest {
+    pub id: i64,
+    pub name: String,
+    pub platform: String,
+    pub execution_time: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -75,9 +171,17 @@ pub struct CreateTestResultBase {
     pub status: Status,
     pub execution_time: Option<i64>,
     pub log: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<TestStep>>, // in the order they ran
     pub screenshot_id: Option<i64>,
     pub created_by: Option<String>,
-    pub time_created: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suite_path: Option<String>, // e.g. the file or class of the test, kept in the test case catalog
+    #[serde(default, deserialize_with = "timestamp::deserialize_option", skip_serializing_if = "Option::is_none")]
+    pub time_created: Option<i64>, // the server time when omitted
+    // Set by the client to publish a test result once, replays with the same client_id are skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -87,16 +191,6 @@ pub struct CreateTestResult {
     pub base: CreateTestResultBase,
 }
 
-impl CreateTestResult {
-   pub fn from_json(
-        json: &str,
-        execution_id: i64,
-    ) -> Result<Self, serde_json::Error> {
-        let base: CreateTestResultBase = serde_json::from_str(json)?;
-        Ok(CreateTestResult { execution_id, base })
-    }
-}
-
 impl Deref for CreateTestResult {
     type Target = CreateTestResultBase;
 
@@ -116,16 +210,340 @@ pub struct StreamResponse {
     pub execution_id: i64,
     pub received: i64,
     pub inserted: i64,
+    #[serde(default)]
+    pub duplicates: i64, // lines whose client_id was already written, they aren't written again
     pub failed: i64,
     pub failed_items: Option<Vec<FailedItem>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FailedItem {
+    #[serde(default)]
+    pub index: i64, // line of the stream or element of the batch, starting at 0
     pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>, // the offending field of an invalid item
     pub raw_payload: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SearchHit {
+    pub id: i64,
+    pub execution_id: i64,
+    pub name: String,
+    pub platform: String,
+    pub status: Status,
+    pub time_created: i64,
+    pub name_highlight: String,
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_next: bool,
+    pub items: Vec<SearchHit>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClusterExample {
+    pub id: i64,
+    pub execution_id: i64,
+    pub name: String,
+    pub platform: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FailureCluster {
+    pub signature: String,
+    #[sqlx(skip)]
+    pub message: String, // normalized failure log of the latest example
+    pub count: i64,
+    pub executions: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    #[sqlx(skip)]
+    pub examples: Vec<ClusterExample>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClusterListResponse {
+    pub execution_id: Option<i64>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_next: bool,
+    pub items: Vec<FailureCluster>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TestHistoryItem {
+    pub result_id: i64,
+    pub execution_id: i64,
+    pub execution_name: String,
+    pub execution_tag: Option<String>,
+    pub execution_created_by: Option<String>,
+    pub execution_time_created: i64,
+    pub platform: String,
+    pub status: Status,
+    pub execution_time: Option<i64>,
+    pub counter: i64,
+    pub time_created: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestHistoryResponse {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_case_id: Option<i64>, // only set for the history of a test case
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_next: bool,
+    pub items: Vec<TestHistoryItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DurationStats {
+    pub platform: String,
+    pub samples: i64,
+    pub min: i64,
+    pub max: i64,
+    pub avg: f64,
+    pub p50: i64,
+    pub p95: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DurationStatsResponse {
+    pub name: String,
+    pub window: i64,
+    pub items: Vec<DurationStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DurationRegression {
+    pub result_id: i64,
+    pub name: String,
+    pub platform: String,
+    pub execution_time: i64,
+    pub baseline: i64, // p50 of the previous runs
+    pub ratio: f64,
+    pub samples: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegressionResponse {
+    pub execution_id: i64,
+    pub multiple: f64,
+    pub window: i64,
+    pub total: i64,
+    pub items: Vec<DurationRegression>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Quarantine {
+    pub id: i64,
+    pub name: String,
+    pub platform: Option<String>, // None matches every platform
+    pub test_case_id: Option<i64>, // None until the test of the platform is cataloged
+    pub reason: String,
+    pub owner: String,
+    pub expires_at: Option<i64>, // None never expires
+    pub time_created: i64,
+}
+
+/// A quarantine entry targets a test case by `test_case_id`, or a test by `name` and an optional `platform`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateQuarantine {
+    #[serde(default)]
+    pub test_case_id: Option<i64>,
+    #[serde(default)]
+    pub name: String,
+    pub platform: Option<String>,
+    pub reason: String,
+    pub owner: String,
+    #[serde(default, deserialize_with = "timestamp::deserialize_option")]
+    pub expires_at: Option<i64>,
+}
+
+/// A test in the catalog, identified across executions by its name and platform
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TestCase {
+    pub id: i64,
+    pub name: String,
+    pub platform: String,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub component: Option<String>,
+    pub suite_path: Option<String>,
+    pub time_created: i64, // first ingestion of the test
+    pub last_seen: i64, // latest ingestion of the test
+}
+
+/// Fields of a test case to change, the fields left out are kept and the fields set to null are cleared
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTestCase {
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub owner: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub component: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub suite_path: Option<Option<String>>,
+}
+
+/// A field given as null is `Some(None)`, a missing field takes the default `None`
+fn deserialize_nullable<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestCaseListResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_next: bool,
+    pub items: Vec<TestCase>,
+}
+
+/// Maps the tests whose name or suite path matches `pattern` to a team
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct OwnershipRule {
+    pub id: Option<i64>, // None for the rules of the config
+    pub team: String,
+    pub field: String, // name or suite_path
+    pub kind: String, // glob or regex
+    pub pattern: String,
+    pub time_created: Option<i64>,
+}
+
+/// A rule of the config or of the admin API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateOwnershipRule {
+    pub team: String,
+    #[serde(default = "default_ownership_field")]
+    pub field: String,
+    #[serde(default = "default_ownership_kind")]
+    pub kind: String,
+    pub pattern: String,
+}
+
+fn default_ownership_field() -> String {
+    "name".to_string()
+}
+
+fn default_ownership_kind() -> String {
+    "glob".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OwnershipRuleListResponse {
+    pub items: Vec<OwnershipRule>, // in the order they are checked
+}
+
+/// The failed tests of an execution owned by one team, `team` is None for the tests nobody owns
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamFailures {
+    pub team: Option<String>,
+    pub failures: i64,
+    pub tests: Vec<FailedTest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FailedTest {
+    pub id: i64,
+    pub name: String,
+    pub platform: String,
+    pub status: Status,
+    pub test_case_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamFailuresResponse {
+    pub execution_id: i64,
+    pub items: Vec<TeamFailures>, // the team with the most failures first
+}
+
+/// An endpoint notified of execution events
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    #[serde(default, skip_serializing)]
+    pub secret: String, // never returned
+    pub events: Json<Vec<String>>,
+    pub min_pass_rate: Option<f64>,
+    pub time_created: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWebhook {
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub min_pass_rate: Option<f64>, // required by execution.threshold_breached
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookListResponse {
+    pub items: Vec<Webhook>,
+}
+
+/// One event sent, or to send, to a webhook
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: String,
+    pub status: String, // pending, delivered or failed
+    pub attempts: i64,
+    pub next_attempt_at: i64,
+    pub last_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub time_created: i64,
+    pub time_delivered: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookDeliveryListResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_next: bool,
+    pub items: Vec<WebhookDelivery>, // latest first
+}
+
+/// A file attached to a test result, its content is stored once per SHA-256
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TestArtifact {
+    pub id: i64,
+    pub test_result_id: i64,
+    pub name: String,
+    pub kind: String, // screenshot, video, log or file
+    pub content_type: String,
+    pub sha256: String,
+    pub size: i64,
+    pub time_created: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuarantineListResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_next: bool,
+    pub items: Vec<Quarantine>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user id
@@ -144,24 +562,59 @@ pub struct ErrorResponse {
     pub field: Option<String>,
 }
 
+/// Test status, serialized as a single letter.
+/// Descriptive names and JUnit outcomes (passed, failure, error, skipped) are accepted on input.
 #[derive(Debug, Serialize, Deserialize, sqlx::Type, Clone, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
 #[sqlx(type_name = "TEXT")]
 pub enum Status {
+    #[serde(alias = "passed")]
     P, // passed
+    #[serde(alias = "failed", alias = "failure")]
     F, // failed
+    #[serde(alias = "ignored")]
     I, // ignored
+    #[serde(alias = "errored", alias = "error")]
+    E, // errored, e.g. setup failure
+    #[serde(alias = "skipped")]
+    S, // skipped by condition
+    #[serde(alias = "blocked")]
+    B, // blocked
+    #[serde(alias = "known_failure", alias = "quarantined")]
+    K, // known failure / quarantined
 }
 
 impl Status {
     pub fn value_of(val: &str) -> Result<Status, String> {
-        match val {
-            "P" | "p" => Ok(Status::P),
-            "F" | "f" => Ok(Status::F),
-            "I" | "i" => Ok(Status::I),
-            _ => Err(format!("Invalid status: {}. Expected I, P, or F", val)),
+        match val.to_ascii_lowercase().as_str() {
+            "p" | "passed" => Ok(Status::P),
+            "f" | "failed" | "failure" => Ok(Status::F),
+            "i" | "ignored" => Ok(Status::I),
+            "e" | "errored" | "error" => Ok(Status::E),
+            "s" | "skipped" => Ok(Status::S),
+            "b" | "blocked" => Ok(Status::B),
+            "k" | "known_failure" | "quarantined" => Ok(Status::K),
+            _ => Err(format!("Invalid status: {}. Expected P, F, I, E, S, B or K", val)),
+        }
+    }
+
+    /// The single-letter code stored in the database and sent on the wire
+    pub fn code(&self) -> &'static str {
+        match self {
+            Status::P => "P",
+            Status::F => "F",
+            Status::I => "I",
+            Status::E => "E",
+            Status::S => "S",
+            Status::B => "B",
+            Status::K => "K",
         }
     }
+
+    /// Whether the test failed on its own, such results carry a failure signature
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::F | Status::E)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
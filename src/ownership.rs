@@ -0,0 +1,150 @@
+// src/ownership.rs
+// Ownership rules: which team owns a test, resolved when test results are read
+
+use globset::{GlobBuilder, GlobMatcher};
+use parking_lot::RwLock;
+use regex::Regex;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+
+use crate::error::ValidationError;
+use crate::models::{CreateOwnershipRule, OwnershipRule, TestResult};
+
+pub const RULE_FIELDS: &[&str] = &["name", "suite_path"];
+pub const RULE_KINDS: &[&str] = &["glob", "regex"];
+
+enum Matcher {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Matcher::Glob(glob) => glob.is_match(value),
+            Matcher::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+struct CompiledRule {
+    team: String,
+    on_suite_path: bool,
+    matcher: Matcher,
+}
+
+/// The rules of the config followed by the rules of the admin API, the first matching rule wins
+pub struct OwnershipRules {
+    rules: Vec<OwnershipRule>,
+    compiled: Vec<CompiledRule>,
+}
+
+impl OwnershipRules {
+    /// Compile the rules of the config and of the database, a rule of the config which doesn't compile is an error
+    pub async fn load(conn: &mut SqliteConnection, config_rules: &[CreateOwnershipRule]) -> anyhow::Result<Self> {
+        let mut rules: Vec<OwnershipRule> = config_rules
+            .iter()
+            .map(|rule| OwnershipRule {
+                id: None,
+                team: rule.team.clone(),
+                field: rule.field.clone(),
+                kind: rule.kind.clone(),
+                pattern: rule.pattern.clone(),
+                time_created: None,
+            })
+            .collect();
+        rules.extend(
+            sqlx::query_as::<_, OwnershipRule>("SELECT * FROM ownership_rule ORDER BY id")
+                .fetch_all(conn)
+                .await?,
+        );
+
+        let compiled = rules
+            .iter()
+            .map(|rule| compile(&rule.field, &rule.kind, &rule.pattern).map(|matcher| CompiledRule {
+                team: rule.team.clone(),
+                on_suite_path: rule.field == "suite_path",
+                matcher,
+            }))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!("Invalid ownership rule: {}", e))?;
+
+        Ok(OwnershipRules { rules, compiled })
+    }
+
+    pub fn rules(&self) -> &[OwnershipRule] {
+        &self.rules
+    }
+
+    /// The team of the first rule matching the test name or its suite path
+    pub fn team_of(&self, name: &str, suite_path: Option<&str>) -> Option<&str> {
+        self.compiled
+            .iter()
+            .find(|rule| match rule.on_suite_path {
+                true => suite_path.is_some_and(|path| rule.matcher.is_match(path)),
+                false => rule.matcher.is_match(name),
+            })
+            .map(|rule| rule.team.as_str())
+    }
+}
+
+/// Check that the field, kind and pattern of a rule compile before it is stored
+pub fn validate_rule(rule: &CreateOwnershipRule) -> Result<(), ValidationError> {
+    compile(&rule.field, &rule.kind, &rule.pattern).map(|_| ())
+}
+
+fn compile(field: &str, kind: &str, pattern: &str) -> Result<Matcher, ValidationError> {
+    if !RULE_FIELDS.contains(&field) {
+        return Err(ValidationError::new("field", format!("must be one of {}", RULE_FIELDS.join(", "))));
+    }
+    if pattern.is_empty() {
+        return Err(ValidationError::new("pattern", "must not be empty"));
+    }
+    match kind {
+        // `*` also matches `/` unlike in paths, test names often contain it
+        "glob" => GlobBuilder::new(pattern)
+            .build()
+            .map(|glob| Matcher::Glob(glob.compile_matcher()))
+            .map_err(|e| ValidationError::new("pattern", e.kind().to_string())),
+        "regex" => Regex::new(pattern)
+            .map(Matcher::Regex)
+            .map_err(|e| ValidationError::new("pattern", e.to_string())),
+        _ => Err(ValidationError::new("kind", format!("must be one of {}", RULE_KINDS.join(", ")))),
+    }
+}
+
+/// Set the team of each test result: the owner of its test case, else the first matching rule
+pub async fn assign_teams(
+    conn: &mut SqliteConnection,
+    rules: &RwLock<OwnershipRules>,
+    results: &mut [TestResult],
+) -> sqlx::Result<()> {
+    let ids: Vec<i64> = results.iter().filter_map(|result| result.test_case_id).collect();
+    let mut test_cases: HashMap<i64, (Option<String>, Option<String>)> = HashMap::new();
+    if !ids.is_empty() {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT id, owner, suite_path FROM test_case WHERE id IN (");
+        let mut separated = query.separated(", ");
+        for id in &ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        let rows = query
+            .build_query_as::<(i64, Option<String>, Option<String>)>()
+            .fetch_all(conn)
+            .await?;
+        test_cases.extend(rows.into_iter().map(|(id, owner, suite_path)| (id, (owner, suite_path))));
+    }
+
+    let rules = rules.read();
+    for result in results.iter_mut() {
+        let (owner, suite_path) = result
+            .test_case_id
+            .and_then(|id| test_cases.get(&id))
+            .map_or((None, None), |(owner, suite_path)| (owner.as_deref(), suite_path.as_deref()));
+        result.team = owner
+            .or_else(|| rules.team_of(&result.name, suite_path))
+            .map(str::to_string);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+// src/routes/artifact.rs
+// Define test artifact API here: attachments of test results uploaded as raw bodies
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+use std::io;
+use tokio_util::io::ReaderStream;
+
+use crate::artifact::{ArtifactStore, StagedBlob, StoreError};
+use crate::database::{get_test_artifact, insert_test_artifact, list_test_artifacts, NewArtifact};
+use crate::error::{AppError, ValidationError};
+use crate::models::TestArtifact;
+use crate::state::AppState;
+
+const ARTIFACT_KINDS: [&str; 4] = ["screenshot", "video", "log", "file"];
+
+const MAX_NAME_LEN: usize = 255;
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/result/:id/artifacts", get(get_artifacts).post(upload_artifact))
+        .route("/api/artifact/:id", get(download_artifact))
+}
+
+/// Store the body as an attachment of the test result, `name` and `kind` are query parameters
+async fn upload_artifact(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<(StatusCode, Json<TestArtifact>), AppError> {
+    let name = params.get("name").map(|name| name.trim()).unwrap_or_default();
+    if name.is_empty() {
+        return Err(ValidationError::new("name", "must not be blank").into());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(ValidationError::new("name", format!("must have at most {} characters", MAX_NAME_LEN)).into());
+    }
+
+    let kind = params.get("kind").map(String::as_str).unwrap_or("file");
+    if !ARTIFACT_KINDS.contains(&kind) {
+        return Err(ValidationError::new("kind", format!("must be one of {}", ARTIFACT_KINDS.join(", "))).into());
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or(DEFAULT_CONTENT_TYPE);
+
+    {
+        let mut conn = state.pool.acquire().await?;
+        check_test_result_existing(&mut conn, id).await?;
+    }
+
+    let store = ArtifactStore::new(&state.config.artifact);
+    let staged = store.stage(body.into_data_stream()).await.map_err(|e| match e {
+        StoreError::TooLarge(max_size) => AppError::PayloadTooLarge(format!("Artifact is larger than {} bytes", max_size)),
+        StoreError::Upload(e) => AppError::BadRequest(format!("Failed to read the upload: {}", e)),
+        StoreError::Io(e) => AppError::internal(e),
+    })?;
+
+    let artifact = NewArtifact {
+        name,
+        kind,
+        content_type,
+        sha256: &staged.sha256,
+        size: staged.size,
+    };
+    let saved = save_artifact(&state, &store, id, &artifact, &staged).await;
+    store.discard(staged).await;
+
+    Ok((StatusCode::CREATED, Json(saved?)))
+}
+
+/// Record the attachment and move its blob in place.
+/// The insert takes the write lock before the file is moved, see `ArtifactStore::sweep`.
+async fn save_artifact(
+    state: &AppState,
+    store: &ArtifactStore,
+    test_result_id: i64,
+    artifact: &NewArtifact<'_>,
+    staged: &StagedBlob,
+) -> Result<TestArtifact, AppError> {
+    let mut tx = state.pool.begin().await?;
+    let saved = insert_test_artifact(&mut tx, test_result_id, artifact).await?;
+    store.commit(staged).await.map_err(AppError::internal)?;
+    tx.commit().await?;
+    Ok(saved)
+}
+
+async fn get_artifacts(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<TestArtifact>>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+    check_test_result_existing(&mut conn, id).await?;
+
+    let artifacts = list_test_artifacts(&mut conn, id).await?;
+    Ok(Json(artifacts))
+}
+
+/// Stream the content of an attachment with its content type and name
+async fn download_artifact(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Response, AppError> {
+    let artifact = {
+        let mut conn = state.pool.acquire().await?;
+        get_test_artifact(&mut conn, id).await.map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::NotFound("Artifact not found".to_string()),
+            e => e.into(),
+        })?
+    };
+
+    let store = ArtifactStore::new(&state.config.artifact);
+    let file = tokio::fs::File::open(store.path_of(&artifact.sha256)).await.map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => AppError::NotFound("Artifact content not found".to_string()),
+        _ => AppError::internal(e),
+    })?;
+
+    let headers = [
+        (header::CONTENT_TYPE, artifact.content_type.clone()),
+        (header::CONTENT_LENGTH, artifact.size.to_string()),
+        (header::CONTENT_DISPOSITION, content_disposition(&artifact.name)),
+        (header::ETAG, format!("\"{}\"", artifact.sha256)),
+    ];
+    Ok((headers, Body::from_stream(ReaderStream::new(file))).into_response())
+}
+
+async fn check_test_result_existing(conn: &mut SqliteConnection, id: i64) -> Result<(), AppError> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM test_result WHERE id = ?")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .map(|_| ())
+        .ok_or(AppError::NotFound("Test result not found".to_string()))
+}
+
+/// `attachment` with the name as file name, quotes and control characters are replaced
+fn content_disposition(name: &str) -> String {
+    let file_name: String = name
+        .chars()
+        .map(|c| if c.is_control() || c == '"' || c == '\\' { '_' } else { c })
+        .collect();
+    format!("attachment; filename=\"{}\"", file_name)
+}
@@ -0,0 +1,142 @@
+// src/routes/cluster.rs
+// Define failure cluster API here
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use sqlx::{QueryBuilder, Sqlite};
+use std::collections::HashMap;
+
+use super::filter::ResultFilter;
+use super::pagination::limit_of;
+use crate::database::full_log;
+use crate::error::AppError;
+use crate::models::{ClusterExample, ClusterListResponse, FailureCluster};
+use crate::signature::normalize_failure_log;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/clusters", get(get_clusters))
+        .route("/api/execution/:id/clusters", get(get_execution_clusters))
+}
+
+async fn get_clusters(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<ClusterListResponse>, AppError> {
+    list_clusters(&state, None, &params).await.map(Json)
+}
+
+async fn get_execution_clusters(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<ClusterListResponse>, AppError> {
+    list_clusters(&state, Some(id), &params).await.map(Json)
+}
+
+/// Example of a cluster, the log is only selected for the latest one
+#[derive(sqlx::FromRow)]
+struct ExampleRow {
+    id: i64,
+    execution_id: i64,
+    name: String,
+    platform: String,
+    log: Option<String>,
+    signature: String,
+}
+
+/// Group failed test results by failure signature, biggest clusters first
+async fn list_clusters(
+    state: &AppState,
+    execution_id: Option<i64>,
+    params: &HashMap<String, String>,
+) -> Result<ClusterListResponse, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let limit = limit_of(params)?;
+    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+    let examples: i64 = params.get("examples").and_then(|s| s.parse().ok()).unwrap_or(3).clamp(0, 10);
+
+    let filter = ResultFilter::from_params(params)?;
+
+    let push_conditions = |builder: &mut QueryBuilder<'_, Sqlite>| {
+        builder.push(" FROM test_result WHERE status IN ('F', 'E') AND failure_signature IS NOT NULL");
+        if let Some(execution_id) = execution_id {
+            builder.push(" AND execution_id = ");
+            builder.push_bind(execution_id);
+        }
+        filter.push_conditions(builder);
+    };
+
+    // Fetch total count
+    let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(DISTINCT failure_signature)");
+    push_conditions(&mut count_query);
+    let total = count_query.build_query_scalar::<i64>().fetch_one(&mut *conn).await?;
+
+    // Fetch clusters
+    let mut query = QueryBuilder::<Sqlite>::new(
+        "SELECT failure_signature AS signature, COUNT(*) AS count, COUNT(DISTINCT execution_id) AS executions, \
+         MIN(time_created) AS first_seen, MAX(time_created) AS last_seen",
+    );
+    push_conditions(&mut query);
+    query.push(" GROUP BY failure_signature ORDER BY count DESC, last_seen DESC LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+    let mut items = query.build_query_as::<FailureCluster>().fetch_all(&mut *conn).await?;
+
+    // Attach the latest examples of every cluster, within the same filters, in one query for the page
+    let mut examples_of: HashMap<String, Vec<ExampleRow>> = HashMap::new();
+    if !items.is_empty() {
+        let mut example_query = QueryBuilder::<Sqlite>::new(
+            "SELECT id, execution_id, name, platform, CASE WHEN row_rank = 1 THEN log END AS log, signature FROM (\
+             SELECT id, execution_id, name, platform, log, failure_signature AS signature, \
+             ROW_NUMBER() OVER (PARTITION BY failure_signature ORDER BY id DESC) AS row_rank",
+        );
+        push_conditions(&mut example_query);
+        example_query.push(" AND failure_signature IN (");
+        let mut separated = example_query.separated(", ");
+        for cluster in &items {
+            separated.push_bind(cluster.signature.clone());
+        }
+        separated.push_unseparated(")) WHERE row_rank <= ");
+        example_query.push_bind(examples.max(1));
+        example_query.push(" ORDER BY signature, id DESC");
+        for row in example_query.build_query_as::<ExampleRow>().fetch_all(&mut *conn).await? {
+            examples_of.entry(row.signature.clone()).or_default().push(row);
+        }
+    }
+
+    for cluster in items.iter_mut() {
+        let rows = examples_of.remove(&cluster.signature).unwrap_or_default();
+
+        let example_log = match rows.first() {
+            Some(row) => full_log(&mut conn, row.id, row.log.clone()).await?,
+            None => None,
+        };
+        if let Some(log) = example_log {
+            cluster.message = normalize_failure_log(&log);
+        }
+        cluster.examples = rows
+            .into_iter()
+            .take(examples as usize)
+            .map(|row| ClusterExample { id: row.id, execution_id: row.execution_id, name: row.name, platform: row.platform })
+            .collect();
+    }
+
+    let has_next = (offset + limit) < total;
+
+    Ok(ClusterListResponse {
+        execution_id,
+        total,
+        limit,
+        offset,
+        has_next,
+        items,
+    })
+}
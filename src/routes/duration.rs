@@ -0,0 +1,196 @@
+// src/routes/duration.rs
+// Define duration trend and performance regression API here
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use std::collections::{BTreeMap, HashMap};
+
+use super::filter::timestamp_of;
+use crate::error::AppError;
+use crate::models::{DurationRegression, DurationStats, DurationStatsResponse, RegressionResponse};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/tests/:name/duration", get(get_test_duration))
+        .route("/api/execution/:id/regressions", get(get_execution_regressions))
+}
+
+/// Nearest-rank percentile of sorted values, `p` within 0..=1
+fn percentile(sorted: &[i64], p: f64) -> i64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn window_of(state: &AppState, params: &HashMap<String, String>) -> i64 {
+    params.get("window")
+        .and_then(|s| s.parse().ok())
+        .filter(|window: &i64| *window > 0)
+        .unwrap_or(state.config.performance.baseline_window)
+        .min(1000)
+}
+
+/// Duration statistics of a test over its latest runs, per platform
+async fn get_test_duration(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<DurationStatsResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let window = window_of(&state, &params);
+
+    let mut conditions = "WHERE name = ? AND execution_time IS NOT NULL".to_string();
+    let mut bindings = vec![name.clone()];
+
+    // Add filters
+    if let Some(platform) = params.get("platform") {
+        conditions.push_str(" AND platform = ?");
+        bindings.push(platform.clone());
+    }
+
+    if let Some(from) = timestamp_of(&params, "from")? {
+        conditions.push_str(" AND time_created >= ?");
+        bindings.push(from.to_string());
+    }
+
+    if let Some(to) = timestamp_of(&params, "to")? {
+        conditions.push_str(" AND time_created <= ?");
+        bindings.push(to.to_string());
+    }
+
+    let query = format!(
+        "SELECT platform, execution_time FROM ( \
+           SELECT platform, execution_time, \
+                  ROW_NUMBER() OVER (PARTITION BY platform ORDER BY time_received DESC, id DESC) AS rn \
+           FROM test_result {} \
+         ) WHERE rn <= ? ORDER BY platform, execution_time",
+        conditions
+    );
+
+    let mut query_builder = sqlx::query_as::<_, (String, i64)>(&query);
+    for binding in &bindings {
+        query_builder = query_builder.bind(binding);
+    }
+    query_builder = query_builder.bind(window);
+    let rows = query_builder.fetch_all(&mut *conn).await?;
+
+    // durations are sorted per platform by the query
+    let mut durations: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for (platform, execution_time) in rows {
+        durations.entry(platform).or_default().push(execution_time);
+    }
+
+    let items = durations
+        .into_iter()
+        .map(|(platform, sorted)| DurationStats {
+            platform,
+            samples: sorted.len() as i64,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            avg: sorted.iter().sum::<i64>() as f64 / sorted.len() as f64,
+            p50: percentile(&sorted, 0.5),
+            p95: percentile(&sorted, 0.95),
+        })
+        .collect();
+
+    let response = DurationStatsResponse {
+        name,
+        window,
+        items,
+    };
+
+    Ok(Json(response))
+}
+
+/// Results of an execution which ran slower than `multiple` times the p50 of their previous runs
+async fn get_execution_regressions(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<RegressionResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let performance = &state.config.performance;
+    let window = window_of(&state, &params);
+    let multiple: f64 = params.get("multiple")
+        .and_then(|s| s.parse().ok())
+        .filter(|multiple: &f64| *multiple > 0.0)
+        .unwrap_or(performance.regression_multiple);
+
+    let results = sqlx::query_as::<_, (i64, String, String, i64)>(
+        "SELECT id, name, platform, execution_time FROM test_result WHERE execution_id = ? AND execution_time IS NOT NULL"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Previous runs of the same test on the same platform in other executions, latest first
+    let baseline_rows = sqlx::query_as::<_, (i64, i64)>(
+        r#"
+        SELECT result_id, execution_time FROM (
+            SELECT cur.id AS result_id, h.execution_time,
+                   ROW_NUMBER() OVER (PARTITION BY cur.id ORDER BY h.time_received DESC, h.id DESC) AS rn
+            FROM test_result cur
+            JOIN test_result h ON h.name = cur.name AND h.platform = cur.platform
+            WHERE cur.execution_id = ? AND cur.execution_time IS NOT NULL
+              AND h.execution_id != cur.execution_id
+              AND h.execution_time IS NOT NULL
+              AND h.time_received <= cur.time_received
+        ) WHERE rn <= ?
+        ORDER BY result_id, execution_time
+        "#
+    )
+    .bind(id)
+    .bind(window)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut baselines: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (result_id, execution_time) in baseline_rows {
+        baselines.entry(result_id).or_default().push(execution_time);
+    }
+
+    let mut items: Vec<DurationRegression> = results
+        .into_iter()
+        .filter_map(|(result_id, name, platform, execution_time)| {
+            let sorted = baselines.get(&result_id)?;
+            if sorted.len() < performance.baseline_min_samples {
+                return None;
+            }
+            let baseline = percentile(sorted, 0.5);
+            if baseline <= 0 || (execution_time as f64) <= multiple * baseline as f64 {
+                return None;
+            }
+            Some(DurationRegression {
+                result_id,
+                name,
+                platform,
+                execution_time,
+                baseline,
+                ratio: execution_time as f64 / baseline as f64,
+                samples: sorted.len() as i64,
+            })
+        })
+        .collect();
+
+    // the worst regression first
+    items.sort_by(|a, b| b.ratio.total_cmp(&a.ratio));
+
+    let response = RegressionResponse {
+        execution_id: id,
+        multiple,
+        window,
+        total: items.len() as i64,
+        items,
+    };
+
+    Ok(Json(response))
+}
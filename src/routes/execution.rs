@@ -3,21 +3,41 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, Uri},
     response::Json,
     routing::{get, post},
     Router,
 };
+use sqlx::{QueryBuilder, Sqlite};
 use std::collections::HashMap;
+use std::time::Duration;
 
-use crate::models::{Execution, CreateExecution, ExecutionListResponse, ExecutionResultsResponse, TestResult, Summary, SuggestedItem, SuggestQuery, SuggestResponse};
+use super::filter::ResultFilter;
+use super::idempotency::{idempotent, RequestBody};
+use super::pagination::Page;
+use super::sort::{Sort, EXECUTION_SORT_FIELDS, RESULT_SORT_FIELDS};
+use crate::background::writer::WriterName;
+use crate::error::AppError;
+use crate::models::{Execution, CreateExecution, ExecutionListResponse, ExecutionResultsResponse, ExecutionSummary, ExecutionSummariesResponse, TestResult, SuggestedItem, SuggestQuery, SuggestResponse};
+use crate::database::{check_execution_existing, create_execution as db_create_execution, load_new_failures, load_summaries, TEST_RESULT_LISTING_COLUMNS};
+use crate::ownership::assign_teams;
 use crate::state::AppState;
+use crate::validation::ValidJson;
+use crate::webhook::emit_execution_events;
+
+// Maximum number of executions in one summaries request
+const MAX_SUMMARY_IDS: usize = 100;
+
+// How long finishing an execution waits for its results already accepted to be written, 503 past it
+const FINISH_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub fn routes(app_state: &AppState) -> Router<AppState> {
     let mut router = Router::new()
         .route("/api/execution", post(create_execution))
         .route("/api/executions", get(get_executions))
-        .route("/api/execution/:id/result", get(get_execution_results));
+        .route("/api/executions/summary", get(get_execution_summaries))
+        .route("/api/execution/:id/result", get(get_execution_results))
+        .route("/api/execution/:id/finish", post(finish_execution));
 
     // Conditionally add the suggest route based on configuration
     if app_state.config.execution_suggest.enabled {
@@ -27,22 +47,32 @@ pub fn routes(app_state: &AppState) -> Router<AppState> {
     router
 }
 
+/// Quarantined test results are left out of the summaries unless include_quarantined=true
+fn include_quarantined(params: &HashMap<String, String>) -> bool {
+    params.get("include_quarantined").map(|s| s.as_str()) == Some("true")
+}
+
 async fn create_execution(
     State(state): State<AppState>,
-    Json(payload): Json<CreateExecution>,
-) -> Result<(StatusCode, Json<Execution>), (StatusCode, String)> {
-    let mut conn = state.pool.acquire().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    headers: HeaderMap,
+    uri: Uri,
+    ValidJson(payload): ValidJson<CreateExecution>,
+) -> Result<(StatusCode, Json<Execution>), AppError> {
+    idempotent(&state, &headers, &uri, RequestBody::parsed(&payload)?, async {
+        let execution = insert_execution(&state, &payload).await?;
+        Ok((StatusCode::CREATED, Json(execution)))
+    }).await
+}
+
+/// Create an execution and make its name suggestible.
+/// Shared with the stream API, whose header line creates the execution.
+pub(super) async fn insert_execution(
+    state: &AppState,
+    payload: &CreateExecution,
+) -> Result<Execution, AppError> {
+    let mut conn = state.pool.acquire().await?;
     
-    let execution = sqlx::query_as::<_, Execution>(
-        "INSERT INTO execution (name, tag, created_by, time_created) VALUES (?, ?, ?, ?) RETURNING *"
-    )
-    .bind(&payload.name)
-    .bind(&payload.tag)
-    .bind(&payload.created_by)
-    .bind(payload.time_created)
-    .fetch_one(&mut *conn)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let execution = db_create_execution(&mut conn, payload).await?;
 
     // Add the new execution name to the prefix trie for suggestions (if enabled)
     if let Some(ref trie) = state.execution_prefix_trie {
@@ -54,65 +84,112 @@ async fn create_execution(
         trie_write.insert(&execution.name, item);
     }
 
-    Ok((StatusCode::CREATED, Json(execution)))
+    Ok(execution)
+}
+
+/// Mark an execution finished and queue its webhook events.
+/// The results accepted before the call are written first, so that the events account for them.
+async fn finish_execution(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<Execution>, AppError> {
+    state.writer_manager
+        .flush(WriterName::Main, id, FINISH_FLUSH_TIMEOUT)
+        .await
+        .map_err(AppError::Overloaded)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let execution = sqlx::query_as::<_, Execution>(
+        "UPDATE execution SET time_finished = strftime('%s', 'now') WHERE id = ? AND time_finished IS NULL RETURNING *"
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(execution) = execution else {
+        return Err(match check_execution_existing(&mut tx, id).await {
+            true => AppError::Conflict("Execution is already finished".to_string()),
+            false => AppError::NotFound("Execution not found".to_string()),
+        });
+    };
+
+    let summary = load_summaries(&mut tx, &[id], false)
+        .await?
+        .remove(&id)
+        .unwrap_or_default();
+    let new_failures = load_new_failures(&mut tx, id).await?;
+    emit_execution_events(&mut tx, &execution, &summary, &new_failures).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(execution))
 }
 
 async fn get_executions(
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<ExecutionListResponse>, (StatusCode, String)> {
-    let mut conn = state.pool.acquire().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    let limit: i64 = params.get("limit").and_then(|s| s.parse().ok()).unwrap_or(20).min(100);
-    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
-    
-    let mut query = "SELECT * FROM execution WHERE 1=1".to_string();
-    let mut count_query = "SELECT COUNT(*) FROM execution WHERE 1=1".to_string();
-    let mut bindings = Vec::new();
+) -> Result<Json<ExecutionListResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
     
-    if let Some(created_by) = params.get("created_by") {
-        query.push_str(" AND created_by = ?");
-        count_query.push_str(" AND created_by = ?");
-        bindings.push(created_by.clone());
-    }
-    
-    if let Some(name) = params.get("name") {
-        query.push_str(" AND name Like ?");
-        count_query.push_str(" AND name LIKE ?");
-        bindings.push(format!("{}%", name));
-    }
+    let page = Page::from_params(&params)?;
+    let sort = Sort::from_params(&params, EXECUTION_SORT_FIELDS, true)?;
     
-    if let Some(tag) = params.get("tag") {
-        query.push_str(" AND tag LIKE ?");
-        count_query.push_str(" AND tag LIKE ?");
-        bindings.push(format!("{}%", tag));
-    }
+    let push_conditions = |builder: &mut QueryBuilder<'_, Sqlite>| {
+        if let Some(created_by) = params.get("created_by") {
+            builder.push(" AND created_by = ");
+            builder.push_bind(created_by.clone());
+        }
+        
+        if let Some(name) = params.get("name") {
+            builder.push(" AND name LIKE ");
+            builder.push_bind(format!("{}%", name));
+        }
+        
+        if let Some(tag) = params.get("tag") {
+            builder.push(" AND tag LIKE ");
+            builder.push_bind(format!("{}%", tag));
+        }
+    };
     
-    // Add ordering, limit and offset
-    query.push_str(" ORDER BY id DESC LIMIT ? OFFSET ?");
+    // Fetch total count if requested
+    let total = if page.include_total {
+        let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM execution WHERE 1=1");
+        push_conditions(&mut count_query);
+        Some(count_query.build_query_scalar::<i64>().fetch_one(&mut *conn).await?)
+    } else {
+        None
+    };
     
-    // Fetch total count
-    let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
-    for binding in &bindings {
-        count_query_builder = count_query_builder.bind(binding);
+    // Fetch items, continuing after the cursor if any
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM execution WHERE 1=1");
+    push_conditions(&mut query);
+    if let Some(cursor) = &page.cursor {
+        sort.push_after(&mut query, cursor)?;
     }
-    let total = count_query_builder.fetch_one(&mut *conn).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    sort.push_order_by(&mut query);
+    query.push(" LIMIT ");
+    query.push_bind(page.fetch_limit());
+    query.push(" OFFSET ");
+    query.push_bind(page.offset);
+    let mut items = query.build_query_as::<Execution>().fetch_all(&mut *conn).await?;
+    let (has_next, next_cursor) = page.finish(&mut items, |item| sort.cursor_of(item));
     
-    // Fetch items
-    let mut query_builder = sqlx::query_as::<_, Execution>(&query);
-    for binding in &bindings {
-        query_builder = query_builder.bind(binding);
+    // Attach summaries of the listed executions if requested
+    if params.get("include_summary").map(|s| s.as_str()) == Some("true") {
+        let ids: Vec<i64> = items.iter().filter_map(|item| item.id).collect();
+        let mut summaries = load_summaries(&mut conn, &ids, include_quarantined(&params))
+            .await?;
+        for item in items.iter_mut() {
+            item.summary = item.id.and_then(|id| summaries.remove(&id));
+        }
     }
-    query_builder = query_builder.bind(limit).bind(offset);
-    let items = query_builder.fetch_all(&mut *conn).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    let has_next = (offset + limit) < total;
     
     let response = ExecutionListResponse {
         total,
-        limit,
-        offset,
+        limit: page.limit,
+        offset: page.offset,
         has_next,
+        next_cursor,
         items,
     };
     
@@ -123,78 +200,44 @@ async fn get_execution_results(
     Path(id): Path<i64>,
     State(state): State<AppState>,
     Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<ExecutionResultsResponse>, (StatusCode, String)> {
-    let mut conn = state.pool.acquire().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    let limit: i64 = params.get("limit").and_then(|s| s.parse().ok()).unwrap_or(20).min(100);
-    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
-    
-    // Build the base query for test results
-    let mut query = "SELECT * FROM test_result WHERE execution_id = ?".to_string();
-    let mut count_query = "SELECT COUNT(*) FROM test_result WHERE execution_id = ?".to_string();
-    let mut bindings = vec![id.to_string()];
-    
-    // Add filters
-    if let Some(status) = params.get("status") {
-        query.push_str(" AND status = ?");
-        count_query.push_str(" AND status = ?");
-        bindings.push(status.clone());
-    }
-    
-    if let Some(platform) = params.get("platform") {
-        query.push_str(" AND platform = ?");
-        count_query.push_str(" AND platform = ?");
-        bindings.push(platform.clone());
-    }
+) -> Result<Json<ExecutionResultsResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
     
-    // Add ordering, limit and offset
-    query.push_str(" ORDER BY id ASC LIMIT ? OFFSET ?");
+    let page = Page::from_params(&params)?;
+    let sort = Sort::from_params(&params, RESULT_SORT_FIELDS, false)?;
+    let filter = ResultFilter::from_params(&params)?;
     
-    // Fetch total count
-    let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
-    for binding in &bindings {
-        count_query_builder = count_query_builder.bind(binding);
-    }
-    // Add limit and offset parameters for count query (they're not used but needed for binding)
-    count_query_builder = count_query_builder.bind(limit).bind(offset);
-    let total = count_query_builder.fetch_one(&mut *conn).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    // Fetch total count if requested
+    let total = if page.include_total {
+        let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM test_result WHERE execution_id = ");
+        count_query.push_bind(id);
+        filter.push_conditions(&mut count_query);
+        Some(count_query.build_query_scalar::<i64>().fetch_one(&mut *conn).await?)
+    } else {
+        None
+    };
     
-    // Fetch items
-    let mut query_builder = sqlx::query_as::<_, TestResult>(&query);
-    for binding in &bindings {
-        query_builder = query_builder.bind(binding);
+    // Fetch items, continuing after the cursor if any
+    let mut query = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM test_result WHERE execution_id = ", TEST_RESULT_LISTING_COLUMNS));
+    query.push_bind(id);
+    filter.push_conditions(&mut query);
+    if let Some(cursor) = &page.cursor {
+        sort.push_after(&mut query, cursor)?;
     }
-    query_builder = query_builder.bind(limit).bind(offset);
-    let items = query_builder.fetch_all(&mut *conn).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    
-    let has_next = (offset + limit) < total;
+    sort.push_order_by(&mut query);
+    query.push(" LIMIT ");
+    query.push_bind(page.fetch_limit());
+    query.push(" OFFSET ");
+    query.push_bind(page.offset);
+    let mut items = query.build_query_as::<TestResult>().fetch_all(&mut *conn).await?;
+    let (has_next, next_cursor) = page.finish(&mut items, |item| sort.cursor_of(item));
+    assign_teams(&mut conn, &state.ownership, &mut items).await?;
     
     // Calculate summary if requested
     let summary = if params.get("include_summary").map(|s| s.as_str()) == Some("true") {
-        let pass_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM test_result WHERE execution_id = ? AND status = 'P'")
-            .bind(id)
-            .fetch_one(&mut *conn)
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-            
-        let fail_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM test_result WHERE execution_id = ? AND status = 'F'")
-            .bind(id)
-            .fetch_one(&mut *conn)
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-            
-        let ignor_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM test_result WHERE execution_id = ? AND status = 'I'")
-            .bind(id)
-            .fetch_one(&mut *conn)
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        
-        Some(Summary {
-            total: pass_count + fail_count + ignor_count,
-            pass: pass_count,
-            fail: fail_count,
-            ignor: ignor_count,
-        })
+        load_summaries(&mut conn, &[id], include_quarantined(&params))
+            .await?
+            .remove(&id)
     } else {
         None
     };
@@ -203,23 +246,58 @@ async fn get_execution_results(
         execution_id: id,
         summary,
         total,
-        limit,
-        offset,
+        limit: page.limit,
+        offset: page.offset,
         has_next,
+        next_cursor,
         items,
     };
     
     Ok(Json(response))
 }
 
+/// Summaries of several executions at once, read from the materialized execution stats
+async fn get_execution_summaries(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<ExecutionSummariesResponse>, AppError> {
+    let ids = params.get("ids")
+        .map(|ids| {
+            ids.split(',')
+                .filter(|id| !id.trim().is_empty())
+                .map(|id| id.trim().parse::<i64>())
+                .collect::<Result<Vec<i64>, _>>()
+        })
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid ids: {}", e)))?
+        .unwrap_or_default();
+
+    if ids.len() > MAX_SUMMARY_IDS {
+        return Err(AppError::BadRequest(format!("At most {} ids are allowed", MAX_SUMMARY_IDS)));
+    }
+
+    let mut conn = state.pool.acquire().await?;
+
+    let mut summaries = load_summaries(&mut conn, &ids, include_quarantined(&params))
+        .await?;
+
+    let items = ids
+        .iter()
+        .filter_map(|id| {
+            summaries.remove(id).map(|summary| ExecutionSummary { execution_id: *id, summary })
+        })
+        .collect();
+
+    Ok(Json(ExecutionSummariesResponse { items }))
+}
 
 async fn get_suggested_executions(
     State(state): State<AppState>,
     Query(params): Query<SuggestQuery>,
-) -> Result<Json<SuggestResponse>, (StatusCode, String)> {
+) -> Result<Json<SuggestResponse>, AppError> {
     // Check if execution suggestions are enabled
     if !state.config.execution_suggest.enabled {
-        return Err((StatusCode::NOT_FOUND, "Execution suggestions are disabled".to_string()));
+        return Err(AppError::NotFound("Execution suggestions are disabled".to_string()));
     }
 
     let query = params.query.unwrap_or_default();
@@ -239,7 +317,7 @@ async fn get_suggested_executions(
     
     // Get the prefix trie from the application state (it should exist since we checked enabled flag)
     let trie = state.execution_prefix_trie.as_ref()
-        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Execution prefix trie not initialized".to_string()))?;
+        .ok_or(AppError::Internal("Execution prefix trie not initialized".to_string()))?;
     let trie_read = trie.read();
     let mut suggestions = trie_read.search(&query);
     
@@ -0,0 +1,239 @@
+// src/routes/export.rs
+// Define bulk export API here, results are streamed from the database as NDJSON or CSV
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header,
+    response::Response,
+    routing::get,
+    Router,
+};
+use futures::SinkExt;
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+
+use super::filter::{ids_of, ResultFilter};
+use crate::database::{list_steps_of_results, load_offloaded_log, TEST_RESULT_COLUMNS, TEST_RESULT_LISTING_COLUMNS};
+use crate::error::AppError;
+use crate::models::{CreateTestResult, CreateTestResultBase, TestResult, TestStep};
+use crate::state::AppState;
+
+// Lines buffered between the pages read and the response body
+const EXPORT_BUFFER_LINES: usize = 256;
+
+// Rows read with one connection, their steps are loaded with one query
+const EXPORT_PAGE_ROWS: usize = 256;
+
+const CSV_COLUMNS: [&str; 12] = [
+    "id", "execution_id", "name", "platform", "description", "status",
+    "execution_time", "counter", "screenshot_id", "created_by", "time_created", "log",
+];
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/results/export", get(export_results))
+}
+
+/// A test result with the suite path of its test case, which the ingestion format carries
+#[derive(FromRow)]
+struct ExportRow {
+    #[sqlx(flatten)]
+    result: TestResult,
+    suite_path: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Ndjson,
+    Csv,
+}
+
+/// Stream every test result matching the filters, oldest first
+async fn export_results(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, AppError> {
+    let format = match params.get("format").map(|s| s.as_str()) {
+        None | Some("ndjson") => ExportFormat::Ndjson,
+        Some("csv") => ExportFormat::Csv,
+        Some(other) => return Err(AppError::BadRequest(format!("Invalid format: {}. Expected ndjson or csv", other))),
+    };
+    let include_log = params.get("include_log").map(|s| s.as_str()) == Some("true");
+    let execution_ids = ids_of(&params, "execution_id")?;
+    let filter = ResultFilter::from_params(&params)?;
+
+    let (mut sender, receiver) = futures::channel::mpsc::channel::<Result<String, std::io::Error>>(EXPORT_BUFFER_LINES);
+    let pool = state.pool.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = stream_rows(&pool, &execution_ids, &filter, include_log, format, &mut sender).await {
+            // the client sees the body end with an error instead of a truncated file
+            let _ = sender.send(Err(std::io::Error::other(e))).await;
+        }
+    });
+
+    let (content_type, file_name) = match format {
+        ExportFormat::Ndjson => ("application/x-ndjson", "results.ndjson"),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "results.csv"),
+    };
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name))
+        .body(Body::from_stream(receiver))
+        .map_err(AppError::internal)
+}
+
+/// Read the rows page by page and send them line by line, stops when the client goes away.
+/// A page is read by id after the last row sent, the connection goes back to the pool
+/// before its lines are sent, so that a slow client doesn't hold it.
+async fn stream_rows(
+    pool: &SqlitePool,
+    execution_ids: &[i64],
+    filter: &ResultFilter,
+    include_log: bool,
+    format: ExportFormat,
+    sender: &mut futures::channel::mpsc::Sender<Result<String, std::io::Error>>,
+) -> Result<(), sqlx::Error> {
+    if let ExportFormat::Csv = format {
+        let columns = if include_log { &CSV_COLUMNS[..] } else { &CSV_COLUMNS[..CSV_COLUMNS.len() - 1] };
+        if sender.send(Ok(format!("{}\n", columns.join(",")))).await.is_err() {
+            return Ok(());
+        }
+    }
+
+    let mut after_id = 0;
+    loop {
+        let (page, mut steps) = {
+            let mut conn = pool.acquire().await?;
+            read_page(&mut conn, execution_ids, filter, after_id, include_log, format).await?
+        };
+        let Some(last_id) = page.last().and_then(|row| row.result.id) else {
+            return Ok(());
+        };
+        let is_last = page.len() < EXPORT_PAGE_ROWS;
+        after_id = last_id;
+
+        for row in page {
+            let line = match format {
+                ExportFormat::Ndjson => {
+                    let row_steps = row.result.id.and_then(|id| steps.remove(&id));
+                    ndjson_line(row, row_steps)
+                }
+                ExportFormat::Csv => csv_line(row.result, include_log),
+            };
+            if sender.send(Ok(line)).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        if is_last {
+            return Ok(());
+        }
+    }
+}
+
+/// The rows after an id with their offloaded logs, and their steps in NDJSON
+async fn read_page(
+    conn: &mut SqliteConnection,
+    execution_ids: &[i64],
+    filter: &ResultFilter,
+    after_id: i64,
+    include_log: bool,
+    format: ExportFormat,
+) -> Result<(Vec<ExportRow>, HashMap<i64, Vec<TestStep>>), sqlx::Error> {
+    let selected = if include_log { TEST_RESULT_COLUMNS } else { TEST_RESULT_LISTING_COLUMNS };
+    let mut query = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT r.*, c.suite_path FROM (SELECT {} FROM test_result WHERE id > ",
+        selected
+    ));
+    query.push_bind(after_id);
+    if !execution_ids.is_empty() {
+        query.push(" AND execution_id IN (");
+        let mut separated = query.separated(", ");
+        for execution_id in execution_ids {
+            separated.push_bind(*execution_id);
+        }
+        separated.push_unseparated(")");
+    }
+    filter.push_conditions(&mut query);
+    query.push(" ORDER BY id ASC LIMIT ");
+    query.push_bind(EXPORT_PAGE_ROWS as i64);
+    query.push(") r LEFT JOIN test_case c ON c.id = r.test_case_id ORDER BY r.id ASC");
+
+    let mut page = query.build_query_as::<ExportRow>().fetch_all(&mut *conn).await?;
+
+    if include_log {
+        for row in page.iter_mut() {
+            if row.result.log.is_none() && row.result.log_size.is_some() {
+                row.result.log = load_offloaded_log(&mut *conn, row.result.id.unwrap_or_default()).await?;
+            }
+        }
+    }
+
+    let steps = match format {
+        ExportFormat::Ndjson => {
+            let ids: Vec<i64> = page.iter().filter_map(|row| row.result.id).collect();
+            list_steps_of_results(conn, &ids).await?
+        }
+        ExportFormat::Csv => HashMap::new(),
+    };
+
+    Ok((page, steps))
+}
+
+/// One line in the ingestion format, it can be posted back to the stream API
+fn ndjson_line(row: ExportRow, steps: Option<Vec<TestStep>>) -> String {
+    let ExportRow { result, suite_path } = row;
+    let line = CreateTestResult {
+        execution_id: result.execution_id,
+        base: CreateTestResultBase {
+            name: result.name,
+            platform: result.platform,
+            description: result.description,
+            status: result.status,
+            execution_time: result.execution_time,
+            log: result.log,
+            steps,
+            screenshot_id: result.screenshot_id,
+            created_by: result.created_by,
+            suite_path,
+            time_created: Some(result.time_created),
+            client_id: None,
+        },
+    };
+    format!("{}\n", serde_json::to_string(&line).unwrap_or_default())
+}
+
+fn csv_line(result: TestResult, include_log: bool) -> String {
+    let optional = |value: Option<i64>| value.map(|value| value.to_string()).unwrap_or_default();
+
+    let mut fields = vec![
+        optional(result.id),
+        result.execution_id.to_string(),
+        csv_field(&result.name),
+        csv_field(&result.platform),
+        csv_field(result.description.as_deref().unwrap_or_default()),
+        result.status.code().to_string(),
+        optional(result.execution_time),
+        result.counter.to_string(),
+        optional(result.screenshot_id),
+        csv_field(result.created_by.as_deref().unwrap_or_default()),
+        result.time_created.to_string(),
+    ];
+    if include_log {
+        fields.push(csv_field(result.log.as_deref().unwrap_or_default()));
+    }
+
+    format!("{}\n", fields.join(","))
+}
+
+/// Quote a CSV field when it holds a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,173 @@
+// src/routes/filter.rs
+// Filter grammar of test result queries, turned into bound SQL conditions in one place
+
+use sqlx::{QueryBuilder, Sqlite};
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::models::Status;
+use crate::timestamp;
+
+/// Test result filters read from the query parameters.
+///
+/// List parameters take comma-separated values, ranges are inclusive.
+#[derive(Debug, Default)]
+pub struct ResultFilter {
+    statuses: Vec<String>,
+    platforms: Vec<String>,
+    created_by: Vec<String>,
+    name_prefix: Option<String>,
+    name_contains: Option<String>,
+    min_execution_time: Option<i64>,
+    max_execution_time: Option<i64>,
+    retried: Option<bool>,
+    quarantined: Option<bool>,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+impl ResultFilter {
+    pub fn from_params(params: &HashMap<String, String>) -> Result<Self, AppError> {
+        let statuses = list_of(params, "status")
+            .iter()
+            .map(|status| Status::value_of(status).map(|status| status.code().to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(AppError::BadRequest)?;
+
+        Ok(ResultFilter {
+            statuses,
+            platforms: list_of(params, "platform"),
+            created_by: list_of(params, "created_by"),
+            name_prefix: params.get("name_prefix").filter(|s| !s.is_empty()).cloned(),
+            name_contains: params.get("name_contains").filter(|s| !s.is_empty()).cloned(),
+            min_execution_time: number_of(params, "min_execution_time")?,
+            max_execution_time: number_of(params, "max_execution_time")?,
+            retried: flag_of(params, "retried")?,
+            quarantined: flag_of(params, "quarantined")?,
+            from: timestamp_of(params, "from")?,
+            to: timestamp_of(params, "to")?,
+        })
+    }
+
+    /// Append the conditions as ` AND ...` clauses, every value is bound
+    pub fn push_conditions(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        self.push_conditions_of("", builder);
+    }
+
+    /// The same conditions on the test_result table of a query joining it as `alias`
+    pub fn push_conditions_of(&self, alias: &str, builder: &mut QueryBuilder<'_, Sqlite>) {
+        let column = |name: &str| match alias {
+            "" => name.to_string(),
+            alias => format!("{}.{}", alias, name),
+        };
+
+        push_in(builder, &column("status"), &self.statuses);
+        push_in(builder, &column("platform"), &self.platforms);
+        push_in(builder, &column("created_by"), &self.created_by);
+
+        if let Some(prefix) = &self.name_prefix {
+            builder.push(format!(" AND {} LIKE ", column("name")));
+            builder.push_bind(format!("{}%", escape_like(prefix)));
+            builder.push(" ESCAPE '\\'");
+        }
+
+        if let Some(contains) = &self.name_contains {
+            builder.push(format!(" AND {} LIKE ", column("name")));
+            builder.push_bind(format!("%{}%", escape_like(contains)));
+            builder.push(" ESCAPE '\\'");
+        }
+
+        if let Some(min) = self.min_execution_time {
+            builder.push(format!(" AND {} >= ", column("execution_time")));
+            builder.push_bind(min);
+        }
+
+        if let Some(max) = self.max_execution_time {
+            builder.push(format!(" AND {} <= ", column("execution_time")));
+            builder.push_bind(max);
+        }
+
+        match self.retried {
+            Some(true) => { builder.push(format!(" AND {} > 1", column("counter"))); }
+            Some(false) => { builder.push(format!(" AND {} = 1", column("counter"))); }
+            None => {}
+        }
+
+        if let Some(quarantined) = self.quarantined {
+            builder.push(format!(" AND {} = ", column("quarantined")));
+            builder.push_bind(quarantined);
+        }
+
+        if let Some(from) = self.from {
+            builder.push(format!(" AND {} >= ", column("time_created")));
+            builder.push_bind(from);
+        }
+
+        if let Some(to) = self.to {
+            builder.push(format!(" AND {} <= ", column("time_created")));
+            builder.push_bind(to);
+        }
+    }
+}
+
+/// Comma-separated values of a parameter, blanks are dropped
+fn list_of(params: &HashMap<String, String>, key: &str) -> Vec<String> {
+    params.get(key)
+        .map(|values| {
+            values.split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Comma-separated ids of a parameter
+pub fn ids_of(params: &HashMap<String, String>, key: &str) -> Result<Vec<i64>, AppError> {
+    list_of(params, key)
+        .iter()
+        .map(|id| id.parse::<i64>())
+        .collect::<Result<Vec<i64>, _>>()
+        .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e)))
+}
+
+fn number_of(params: &HashMap<String, String>, key: &str) -> Result<Option<i64>, AppError> {
+    params.get(key)
+        .map(|value| value.trim().parse::<i64>())
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e)))
+}
+
+/// Timestamp of a parameter in any accepted input format, as unix time in seconds
+pub fn timestamp_of(params: &HashMap<String, String>, key: &str) -> Result<Option<i64>, AppError> {
+    params.get(key)
+        .map(|value| timestamp::parse(value))
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e)))
+}
+
+fn flag_of(params: &HashMap<String, String>, key: &str) -> Result<Option<bool>, AppError> {
+    params.get(key)
+        .map(|value| value.trim().parse::<bool>())
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e)))
+}
+
+fn push_in(builder: &mut QueryBuilder<'_, Sqlite>, column: &str, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+
+    builder.push(format!(" AND {} IN (", column));
+    let mut separated = builder.separated(", ");
+    for value in values {
+        separated.push_bind(value.clone());
+    }
+    separated.push_unseparated(")");
+}
+
+/// Escape the LIKE wildcards of user input, `\` is the escape character
+pub fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,110 @@
+// src/routes/history.rs
+// Define cross-execution test history API here
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+
+use super::filter::ResultFilter;
+use super::pagination::limit_of;
+use crate::error::AppError;
+use crate::models::{TestHistoryItem, TestHistoryResponse};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/tests/:name/history", get(get_test_history))
+        .route("/api/test-case/:id/history", get(get_test_case_history))
+}
+
+/// How a test behaved across executions, latest run first
+async fn get_test_history(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<TestHistoryResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    load_history(&mut conn, "r.name", name.clone(), name, None, &params).await.map(Json)
+}
+
+/// The history of a catalog entry, i.e. of the test on one platform
+async fn get_test_case_history(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<TestHistoryResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let name = sqlx::query_scalar::<_, String>("SELECT name FROM test_case WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound("Test case not found".to_string()))?;
+
+    load_history(&mut conn, "r.test_case_id", id.to_string(), name, Some(id), &params).await.map(Json)
+}
+
+/// Results whose `column` equals `binding`, then the filters of the request
+async fn load_history(
+    conn: &mut SqliteConnection,
+    column: &str,
+    binding: String,
+    name: String,
+    test_case_id: Option<i64>,
+    params: &HashMap<String, String>,
+) -> Result<TestHistoryResponse, AppError> {
+    let limit = limit_of(params)?;
+    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let filter = ResultFilter::from_params(params)?;
+
+    let push_conditions = |builder: &mut QueryBuilder<'_, Sqlite>| {
+        builder.push(format!(" FROM test_result r JOIN execution e ON e.id = r.execution_id WHERE {} = ", column));
+        builder.push_bind(binding.clone());
+
+        if let Some(tag) = params.get("tag") {
+            builder.push(" AND e.tag LIKE ");
+            builder.push_bind(format!("{}%", tag));
+        }
+
+        filter.push_conditions_of("r", builder);
+    };
+
+    // Fetch total count
+    let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*)");
+    push_conditions(&mut count_query);
+    let total = count_query.build_query_scalar::<i64>().fetch_one(&mut *conn).await?;
+
+    // Fetch items
+    let mut query = QueryBuilder::<Sqlite>::new(
+        "SELECT r.id AS result_id, r.execution_id, e.name AS execution_name, e.tag AS execution_tag, \
+         e.created_by AS execution_created_by, e.time_created AS execution_time_created, \
+         r.platform, r.status, r.execution_time, r.counter, r.time_created",
+    );
+    push_conditions(&mut query);
+    query.push(" ORDER BY r.time_received DESC, r.id DESC LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+    let items = query.build_query_as::<TestHistoryItem>().fetch_all(&mut *conn).await?;
+
+    let has_next = (offset + limit) < total;
+
+    let response = TestHistoryResponse {
+        name,
+        test_case_id,
+        total,
+        limit,
+        offset,
+        has_next,
+        items,
+    };
+
+    Ok(response)
+}
@@ -0,0 +1,228 @@
+// src/routes/idempotency.rs
+// Idempotency-Key header of the creation requests and client_id of result lines, a replay isn't handled twice
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, StatusCode, Uri},
+    response::Json,
+};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::database::{
+    claim_idempotency_key, client_id_scope, is_idempotency_key_claimed, load_idempotent_response,
+    release_idempotency_key, save_idempotent_response, StoredResponse,
+};
+use crate::state::AppState;
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+// Longest accepted Idempotency-Key header
+const MAX_KEY_LEN: usize = 255;
+
+/// The body of a request with an Idempotency-Key, hashed so that a replay with another body is refused
+pub(super) enum RequestBody {
+    /// A payload already parsed, hashed in its JSON form
+    Parsed(String),
+    /// A body read as a stream by the request, hashed while it is read
+    Streamed(StreamedBody),
+}
+
+impl RequestBody {
+    pub fn parsed(payload: &impl Serialize) -> Result<Self, AppError> {
+        let json = serde_json::to_vec(payload).map_err(AppError::internal)?;
+        Ok(RequestBody::Parsed(hex::encode(Sha256::digest(json))))
+    }
+
+    /// The hash of the body, None when a streamed body couldn't be read to its end.
+    /// A streamed body not taken by the request, i.e. of a replay, is read here.
+    async fn hash(&self) -> Option<String> {
+        match self {
+            RequestBody::Parsed(hash) => Some(hash.clone()),
+            RequestBody::Streamed(body) => {
+                body.chunks().for_each(|_| async {}).await;
+                let state = body.inner.lock();
+                (!state.interrupted).then(|| hex::encode(state.hasher.clone().finalize()))
+            }
+        }
+    }
+}
+
+/// A request body shared between the request, which reads it, and `idempotent`, which hashes it
+#[derive(Clone)]
+pub(super) struct StreamedBody {
+    inner: Arc<Mutex<StreamedState>>,
+}
+
+struct StreamedState {
+    body: Option<Body>,
+    hasher: Sha256,
+    // reading the body failed, e.g. the client disconnected while uploading
+    interrupted: bool,
+}
+
+impl StreamedBody {
+    pub fn new(body: Body) -> Self {
+        StreamedBody { inner: Arc::new(Mutex::new(StreamedState { body: Some(body), hasher: Sha256::new(), interrupted: false })) }
+    }
+
+    /// Take the chunks of the body, hashed as they are read. Empty once taken.
+    pub fn chunks(&self) -> impl Stream<Item = Result<Bytes, axum::Error>> + use<> {
+        let body = self.inner.lock().body.take().unwrap_or_else(Body::empty);
+        let inner = Arc::clone(&self.inner);
+        body.into_data_stream().inspect(move |chunk| match chunk {
+            Ok(chunk) => inner.lock().hasher.update(chunk),
+            Err(_) => inner.lock().interrupted = true,
+        })
+    }
+}
+
+/// Gives a claimed key back when the request doesn't complete: the client disconnected and the
+/// handler was dropped, it panicked, or its response couldn't be stored
+struct ClaimGuard {
+    pool: SqlitePool,
+    scope: String,
+    key: String,
+    armed: bool,
+}
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let (pool, scope, key) = (self.pool.clone(), std::mem::take(&mut self.scope), std::mem::take(&mut self.key));
+        runtime.spawn(async move {
+            let released = match pool.acquire().await {
+                Ok(mut conn) => release_idempotency_key(&mut conn, &scope, &key).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = released {
+                eprintln!("Failed to release Idempotency-Key {}: {}", key, e);
+            }
+        });
+    }
+}
+
+/// Run the request at most once per Idempotency-Key header until the key expires.
+///
+/// A replay gets the stored response of the first request, or 409 while it is still running, or 422 when its body
+/// differs from the first one. The key is released when the request fails, doesn't complete or couldn't read its
+/// whole body, so that it can be retried, and a key held longer than the lease is taken over. Requests without the header always run.
+pub(super) async fn idempotent<T, F>(
+    state: &AppState,
+    headers: &HeaderMap,
+    uri: &Uri,
+    body: RequestBody,
+    request: F,
+) -> Result<(StatusCode, Json<T>), AppError>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<(StatusCode, Json<T>), AppError>>,
+{
+    let Some(key) = key_of(headers)? else {
+        return request.await;
+    };
+    let scope = format!("POST {}", uri.path());
+
+    {
+        let mut conn = state.pool.acquire().await?;
+        let claimed = claim_idempotency_key(
+            &mut conn,
+            &scope,
+            &key,
+            state.config.idempotency.ttl_seconds(),
+            Some(state.config.idempotency.lease_in_sec),
+        ).await?;
+
+        if !claimed {
+            let stored = load_idempotent_response(&mut conn, &scope, &key).await?;
+            return match stored {
+                StoredResponse::Done { status, body: response_body, request_hash } => {
+                    let replayed_hash = body.hash().await;
+                    if request_hash.zip(replayed_hash).is_some_and(|(first, replayed)| first != replayed) {
+                        return Err(AppError::Unprocessable(format!(
+                            "Idempotency-Key {} was used with another request body", key
+                        )));
+                    }
+                    let status = StatusCode::from_u16(status).map_err(AppError::internal)?;
+                    let response_body: T = serde_json::from_str(&response_body).map_err(AppError::internal)?;
+                    Ok((status, Json(response_body)))
+                }
+                StoredResponse::InProgress => Err(AppError::Conflict(format!("A request with Idempotency-Key {} is in progress", key))),
+            };
+        }
+    }
+
+    let mut guard = ClaimGuard { pool: state.pool.clone(), scope, key, armed: true };
+
+    // The connection isn't held while the request runs
+    let response = request.await;
+
+    let mut conn = state.pool.acquire().await?;
+    match &response {
+        Ok((status, Json(response_body))) => match body.hash().await {
+            Some(request_hash) => {
+                let response_body = serde_json::to_string(response_body).map_err(AppError::internal)?;
+                save_idempotent_response(&mut conn, &guard.scope, &guard.key, &request_hash, status.as_u16(), &response_body).await?;
+            }
+            // the response only covers the part of the body which was read, the retry runs again
+            None => release_idempotency_key(&mut conn, &guard.scope, &guard.key).await?,
+        },
+        Err(_) => {
+            release_idempotency_key(&mut conn, &guard.scope, &guard.key).await?;
+        }
+    }
+    guard.armed = false;
+
+    response
+}
+
+/// Whether the test result of an execution with this client_id was already written.
+/// The writer records the client_id with the test result, and skips the replays still queued.
+pub(super) async fn client_id_received(
+    state: &AppState,
+    execution_id: i64,
+    client_id: &str,
+) -> Result<bool, AppError> {
+    let mut conn = state.pool.acquire().await?;
+    Ok(is_idempotency_key_claimed(&mut conn, &client_id_scope(execution_id), client_id).await?)
+}
+
+/// The Idempotency-Key of a request, None without a valid one
+pub(super) fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    key_of(headers).ok().flatten()
+}
+
+/// client_id of a line without one, in a stream or batch sent with an Idempotency-Key.
+/// A retry of an interrupted request sends the same lines, the ones already accepted are skipped.
+pub(super) fn line_client_id(idempotency_key: &str, index: i64, line: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(index.to_be_bytes());
+    hasher.update(line.as_bytes());
+    format!("{}#{}", idempotency_key, hex::encode(hasher.finalize()))
+}
+
+fn key_of(headers: &HeaderMap) -> Result<Option<String>, AppError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    let key = value.to_str()
+        .map_err(|_| AppError::BadRequest("Idempotency-Key must be visible ASCII".to_string()))?
+        .trim();
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(AppError::BadRequest(format!("Idempotency-Key must have 1 to {} characters", MAX_KEY_LEN)));
+    }
+
+    Ok(Some(key.to_string()))
+}
@@ -3,9 +3,23 @@
 
 use axum::Router;
 
+mod artifact;
+mod cluster;
+mod duration;
 mod execution;
+mod export;
+mod filter;
+mod history;
+mod idempotency;
+mod ownership;
+mod pagination;
+mod quarantine;
 mod result;
+mod search;
+mod sort;
 mod stream;
+mod test_case;
+mod webhook;
 
 use crate::state::AppState;
 
@@ -13,5 +27,15 @@ pub fn routes(app_state: &AppState) -> Router<AppState> {
     Router::new()
         .merge(execution::routes(app_state))
         .merge(result::routes())
+        .merge(search::routes())
+        .merge(cluster::routes())
+        .merge(history::routes())
+        .merge(duration::routes())
+        .merge(quarantine::routes())
+        .merge(export::routes())
+        .merge(artifact::routes())
         .merge(stream::routes())
+        .merge(test_case::routes())
+        .merge(ownership::routes())
+        .merge(webhook::routes())
 }
\ No newline at end of file
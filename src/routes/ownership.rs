@@ -0,0 +1,152 @@
+// src/routes/ownership.rs
+// Define ownership rule admin API and per-team failures API here
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, get, post},
+    Router,
+};
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+
+use crate::database::{check_execution_existing, TEST_RESULT_LISTING_COLUMNS};
+use crate::error::AppError;
+use crate::models::{
+    CreateOwnershipRule, FailedTest, OwnershipRule, OwnershipRuleListResponse, TeamFailures, TeamFailuresResponse,
+    TestResult,
+};
+use crate::ownership::{assign_teams, OwnershipRules};
+use crate::state::AppState;
+use crate::validation::ValidJson;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/ownership/rule", post(create_rule))
+        .route("/api/ownership/rules", get(get_rules))
+        .route("/api/ownership/rule/:id", delete(delete_rule))
+        .route("/api/execution/:id/team-failures", get(get_team_failures))
+}
+
+/// Compile the rules as seen by the transaction of a change, before the change is committed
+async fn load_rules(state: &AppState, conn: &mut SqliteConnection) -> Result<OwnershipRules, AppError> {
+    OwnershipRules::load(conn, &state.config.ownership.rules)
+        .await
+        .map_err(AppError::internal)
+}
+
+/// The rule compiles before it is stored, validated by `ValidJson`.
+/// Changes run one at a time under `ownership_reload`, a change whose rules fail to load is rolled back.
+async fn create_rule(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<CreateOwnershipRule>,
+) -> Result<(StatusCode, Json<OwnershipRule>), AppError> {
+    let _reload = state.ownership_reload.lock().await;
+    let mut tx = state.pool.begin().await?;
+
+    let rule = sqlx::query_as::<_, OwnershipRule>(
+        r#"
+        INSERT INTO ownership_rule (team, field, kind, pattern, time_created)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+        RETURNING *
+        "#
+    )
+    .bind(&payload.team)
+    .bind(&payload.field)
+    .bind(&payload.kind)
+    .bind(&payload.pattern)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let rules = load_rules(&state, &mut tx).await?;
+    tx.commit().await?;
+    *state.ownership.write() = rules;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// The rules of the config then the rules of the admin API, in the order they are checked
+async fn get_rules(State(state): State<AppState>) -> Json<OwnershipRuleListResponse> {
+    let items = state.ownership.read().rules().to_vec();
+    Json(OwnershipRuleListResponse { items })
+}
+
+/// Delete a rule of the admin API, the rules of the config can only be changed in the config
+async fn delete_rule(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    let _reload = state.ownership_reload.lock().await;
+    let mut tx = state.pool.begin().await?;
+
+    let deleted = sqlx::query("DELETE FROM ownership_rule WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if deleted == 0 {
+        return Err(AppError::NotFound("Ownership rule not found".to_string()));
+    }
+
+    let rules = load_rules(&state, &mut tx).await?;
+    tx.commit().await?;
+    *state.ownership.write() = rules;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The failed and errored tests of an execution grouped by the team owning them
+async fn get_team_failures(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<TeamFailuresResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    if !check_execution_existing(&mut conn, id).await {
+        return Err(AppError::NotFound("Execution not found".to_string()));
+    }
+
+    // Quarantined tests are known to fail, they are left out like in the summary
+    let mut query = format!(
+        "SELECT {} FROM test_result WHERE execution_id = ? AND status IN ('F', 'E')",
+        TEST_RESULT_LISTING_COLUMNS
+    );
+    if params.get("include_quarantined").map(|s| s.as_str()) != Some("true") {
+        query.push_str(" AND quarantined = 0");
+    }
+    query.push_str(" ORDER BY id");
+
+    let mut results = sqlx::query_as::<_, TestResult>(&query)
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await?;
+    assign_teams(&mut conn, &state.ownership, &mut results).await?;
+
+    let mut teams: HashMap<Option<String>, Vec<FailedTest>> = HashMap::new();
+    for result in results {
+        teams.entry(result.team).or_default().push(FailedTest {
+            id: result.id.unwrap_or_default(),
+            name: result.name,
+            platform: result.platform,
+            status: result.status,
+            test_case_id: result.test_case_id,
+        });
+    }
+
+    let mut items: Vec<TeamFailures> = teams
+        .into_iter()
+        .filter(|(team, _)| params.get("team").is_none_or(|wanted| team.as_ref() == Some(wanted)))
+        .map(|(team, tests)| TeamFailures { team, failures: tests.len() as i64, tests })
+        .collect();
+    // the tests nobody owns come last
+    items.sort_by(|a, b| {
+        a.team.is_none().cmp(&b.team.is_none())
+            .then(b.failures.cmp(&a.failures))
+            .then(a.team.cmp(&b.team))
+    });
+
+    Ok(Json(TeamFailuresResponse { execution_id: id, items }))
+}
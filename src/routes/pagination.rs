@@ -0,0 +1,118 @@
+// src/routes/pagination.rs
+// Offset and keyset (cursor) pagination of listings
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::{AppError, ValidationError};
+
+/// Position after the last item of a page, handed out as an opaque string.
+/// Besides the id it holds the sort the page was listed by and the sort value of the item.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cursor {
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<SortKey>,
+}
+
+/// Sort value of an item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SortKey {
+    Int(i64),
+    Text(String),
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        json.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn decode(value: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest(format!("Invalid cursor: {}", value));
+
+        if !value.len().is_multiple_of(2) || !value.is_ascii() {
+            return Err(invalid());
+        }
+        let bytes = (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+
+        serde_json::from_slice(&bytes).map_err(|_| invalid())
+    }
+}
+
+/// The `limit` of a listing, 20 by default and at most 100
+pub fn limit_of(params: &HashMap<String, String>) -> Result<i64, AppError> {
+    let limit: i64 = params.get("limit").and_then(|s| s.parse().ok()).unwrap_or(20).min(100);
+    // SQLite reads a negative LIMIT as no limit at all
+    if limit < 1 {
+        return Err(ValidationError::new("limit", "must be at least 1").into());
+    }
+    Ok(limit)
+}
+
+/// Page requested by `limit` and either `offset` or `cursor`.
+///
+/// The total is counted by default in offset mode only, `include_total` overrides it.
+#[derive(Debug)]
+pub struct Page {
+    pub limit: i64,
+    pub offset: i64,
+    pub cursor: Option<Cursor>,
+    pub include_total: bool,
+}
+
+impl Page {
+    pub fn from_params(params: &HashMap<String, String>) -> Result<Self, AppError> {
+        let limit = limit_of(params)?;
+        let cursor = params.get("cursor")
+            .filter(|s| !s.is_empty())
+            .map(|s| Cursor::decode(s))
+            .transpose()?;
+
+        // the cursor replaces the offset
+        let offset: i64 = if cursor.is_some() {
+            0
+        } else {
+            params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0)
+        };
+
+        let include_total = match params.get("include_total").map(|s| s.as_str()) {
+            Some("true") => true,
+            Some("false") => false,
+            _ => cursor.is_none(),
+        };
+
+        Ok(Page {
+            limit,
+            offset,
+            cursor,
+            include_total,
+        })
+    }
+
+    /// Rows to fetch: one more than the limit tells whether a next page exists
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+
+    /// Cut the extra row off, returns has_next and the cursor of the next page
+    pub fn finish<T>(&self, items: &mut Vec<T>, cursor_of: impl Fn(&T) -> Option<Cursor>) -> (bool, Option<String>) {
+        let has_next = items.len() as i64 > self.limit;
+        items.truncate(self.limit.max(0) as usize);
+
+        let next_cursor = if has_next {
+            items.last().and_then(cursor_of).map(|cursor| cursor.encode())
+        } else {
+            None
+        };
+
+        (has_next, next_cursor)
+    }
+}
@@ -0,0 +1,257 @@
+// src/routes/quarantine.rs
+// Define test quarantine registry API here
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+
+use super::pagination::limit_of;
+use crate::error::{AppError, ValidationError};
+use crate::models::{CreateQuarantine, Quarantine, QuarantineListResponse};
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/quarantine", post(create_quarantine))
+        .route("/api/quarantines", get(get_quarantines))
+        .route(
+            "/api/quarantine/:id",
+            get(get_quarantine).put(update_quarantine).delete(delete_quarantine),
+        )
+}
+
+fn validate(payload: &CreateQuarantine) -> Result<(), AppError> {
+    if payload.test_case_id.is_none() && payload.name.trim().is_empty() {
+        return Err(ValidationError::new("name", "must not be blank").into());
+    }
+    if payload.reason.trim().is_empty() {
+        return Err(ValidationError::new("reason", "must not be blank").into());
+    }
+    if payload.owner.trim().is_empty() {
+        return Err(ValidationError::new("owner", "must not be blank").into());
+    }
+    Ok(())
+}
+
+/// Link the entry to its test case: `test_case_id` gives the name and platform,
+/// a name and platform take the id of their test case if the test is already cataloged
+async fn resolve_test_case(conn: &mut SqliteConnection, payload: &mut CreateQuarantine) -> Result<(), AppError> {
+    let Some(id) = payload.test_case_id else {
+        if let Some(platform) = payload.platform.as_deref() {
+            payload.test_case_id = sqlx::query_scalar("SELECT id FROM test_case WHERE name = ? AND platform = ?")
+                .bind(&payload.name)
+                .bind(platform)
+                .fetch_optional(conn)
+                .await?;
+        }
+        return Ok(());
+    };
+
+    let (name, platform) = sqlx::query_as::<_, (String, String)>("SELECT name, platform FROM test_case WHERE id = ?")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::from(ValidationError::new("test_case_id", "test case not found")))?;
+
+    if !payload.name.is_empty() && payload.name != name {
+        return Err(ValidationError::new("name", "must match the test case").into());
+    }
+    if payload.platform.as_ref().is_some_and(|value| *value != platform) {
+        return Err(ValidationError::new("platform", "must match the test case").into());
+    }
+
+    payload.name = name;
+    payload.platform = Some(platform);
+    Ok(())
+}
+
+/// A test is quarantined at most once per platform, `except` is the entry being updated
+async fn check_duplicate(
+    conn: &mut SqliteConnection,
+    payload: &CreateQuarantine,
+    except: Option<i64>,
+) -> Result<(), AppError> {
+    let existing = sqlx::query_scalar::<_, i64>(
+        "SELECT id FROM quarantine WHERE name = ? AND platform IS ? AND id IS NOT ?"
+    )
+    .bind(&payload.name)
+    .bind(payload.platform.as_deref())
+    .bind(except)
+    .fetch_optional(conn)
+    .await?;
+
+    match existing {
+        Some(id) => Err(AppError::Conflict(format!("test is already quarantined, quarantine id :: {}", id))),
+        None => Ok(()),
+    }
+}
+
+async fn create_quarantine(
+    State(state): State<AppState>,
+    Json(mut payload): Json<CreateQuarantine>,
+) -> Result<(StatusCode, Json<Quarantine>), AppError> {
+    validate(&payload)?;
+
+    let mut conn = state.pool.acquire().await?;
+    resolve_test_case(&mut conn, &mut payload).await?;
+    check_duplicate(&mut conn, &payload, None).await?;
+
+    let quarantine = sqlx::query_as::<_, Quarantine>(
+        r#"
+        INSERT INTO quarantine (name, platform, test_case_id, reason, owner, expires_at, time_created)
+        VALUES (?, ?, ?, ?, ?, ?, strftime('%s', 'now'))
+        RETURNING *
+        "#
+    )
+    .bind(&payload.name)
+    .bind(payload.platform.as_deref())
+    .bind(payload.test_case_id)
+    .bind(&payload.reason)
+    .bind(&payload.owner)
+    .bind(payload.expires_at)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(quarantine)))
+}
+
+async fn get_quarantines(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<QuarantineListResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let limit = limit_of(&params)?;
+    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let mut conditions = "WHERE 1 = 1".to_string();
+    let mut bindings: Vec<String> = Vec::new();
+
+    // Add filters
+    if let Some(name) = params.get("name") {
+        conditions.push_str(" AND name = ?");
+        bindings.push(name.clone());
+    }
+
+    if let Some(platform) = params.get("platform") {
+        conditions.push_str(" AND platform = ?");
+        bindings.push(platform.clone());
+    }
+
+    if let Some(test_case_id) = params.get("test_case_id") {
+        conditions.push_str(" AND test_case_id = ?");
+        bindings.push(test_case_id.clone());
+    }
+
+    if let Some(owner) = params.get("owner") {
+        conditions.push_str(" AND owner = ?");
+        bindings.push(owner.clone());
+    }
+
+    match params.get("active").map(|s| s.as_str()) {
+        Some("true") => conditions.push_str(" AND (expires_at IS NULL OR expires_at > strftime('%s', 'now'))"),
+        Some("false") => conditions.push_str(" AND expires_at <= strftime('%s', 'now')"),
+        _ => {}
+    }
+
+    let count_query = format!("SELECT COUNT(*) FROM quarantine {}", conditions);
+    let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+    for binding in &bindings {
+        count_query_builder = count_query_builder.bind(binding);
+    }
+    let total = count_query_builder.fetch_one(&mut *conn).await?;
+
+    let query = format!("SELECT * FROM quarantine {} ORDER BY id DESC LIMIT ? OFFSET ?", conditions);
+    let mut query_builder = sqlx::query_as::<_, Quarantine>(&query);
+    for binding in &bindings {
+        query_builder = query_builder.bind(binding);
+    }
+    query_builder = query_builder.bind(limit).bind(offset);
+    let items = query_builder.fetch_all(&mut *conn).await?;
+
+    let has_next = (offset + limit) < total;
+
+    let response = QuarantineListResponse {
+        total,
+        limit,
+        offset,
+        has_next,
+        items,
+    };
+
+    Ok(Json(response))
+}
+
+async fn get_quarantine(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<Quarantine>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let quarantine = sqlx::query_as::<_, Quarantine>("SELECT * FROM quarantine WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound("Quarantine not found".to_string()))?;
+
+    Ok(Json(quarantine))
+}
+
+/// Replace a quarantine entry, results already ingested keep their tag
+async fn update_quarantine(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Json(mut payload): Json<CreateQuarantine>,
+) -> Result<Json<Quarantine>, AppError> {
+    validate(&payload)?;
+
+    let mut conn = state.pool.acquire().await?;
+    resolve_test_case(&mut conn, &mut payload).await?;
+    check_duplicate(&mut conn, &payload, Some(id)).await?;
+
+    let quarantine = sqlx::query_as::<_, Quarantine>(
+        r#"
+        UPDATE quarantine
+        SET name = ?, platform = ?, test_case_id = ?, reason = ?, owner = ?, expires_at = ?
+        WHERE id = ?
+        RETURNING *
+        "#
+    )
+    .bind(&payload.name)
+    .bind(payload.platform.as_deref())
+    .bind(payload.test_case_id)
+    .bind(&payload.reason)
+    .bind(&payload.owner)
+    .bind(payload.expires_at)
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound("Quarantine not found".to_string()))?;
+
+    Ok(Json(quarantine))
+}
+
+async fn delete_quarantine(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let deleted = sqlx::query("DELETE FROM quarantine WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    if deleted == 0 {
+        return Err(AppError::NotFound("Quarantine not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
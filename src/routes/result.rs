@@ -2,81 +2,170 @@
 // Define restful test result API here
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Json, Response},
     routing::{get, post, patch},
     Router,
 };
+use std::collections::HashMap;
 
-use crate::{background::writer::WriterName, database::{check_execution_existing, update_test_result_status as db_update_test_result_status}};
+use crate::error::AppError;
+use crate::{background::writer::WriterName, database::{check_execution_existing, full_log, list_test_steps, update_test_result_status as db_update_test_result_status, TEST_RESULT_COLUMNS}};
 use crate::models::{TestResult, CreateTestResult, CreateTestResultResponse, Status, UpdateStatusRequest};
 use crate::state::AppState;
+use crate::ownership::assign_teams;
+use crate::validation::ValidJson;
+use super::idempotency::{client_id_received, idempotent, RequestBody};
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/api/result", post(create_test_result))
         .route("/api/result/:id", get(get_test_result))
         .route("/api/result/:id/status", patch(update_test_result_status))
+        .route("/api/result/:id/log", get(get_test_result_log))
 }
 
 async fn create_test_result(
     State(state): State<AppState>,
-    Json(payload): Json<CreateTestResult>,
-) -> Result<(StatusCode, Json<CreateTestResultResponse>), (StatusCode, String)> {
-    // Check if the execution exists
-    {
-        let mut conn = state.pool.acquire().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        if !check_execution_existing(&mut *conn, payload.execution_id).await {
-            let error_message = format!("invalid execution_id, no execution is found, execution_id :: {}", payload.execution_id);
-            return Err((StatusCode::BAD_REQUEST, error_message));
+    headers: HeaderMap,
+    uri: Uri,
+    ValidJson(payload): ValidJson<CreateTestResult>,
+) -> Result<(StatusCode, Json<CreateTestResultResponse>), AppError> {
+    idempotent(&state, &headers, &uri, RequestBody::parsed(&payload)?, async {
+        // Check if the execution exists
+        {
+            let mut conn = state.pool.acquire().await?;
+            if !check_execution_existing(&mut *conn, payload.execution_id).await {
+                let error_message = format!("invalid execution_id, no execution is found, execution_id :: {}", payload.execution_id);
+                return Err(AppError::BadRequest(error_message));
+            }
         }
-    }
 
-    // Enqueue the result to be processed by the background writer
-    state.writer_manager.enqueue(WriterName::Main, Box::new(payload)).await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
-    
-    let response = CreateTestResultResponse {
-        status: "delivered".to_string(),
-    };
-    
-    Ok((StatusCode::CREATED, Json(response)))
+        // A test result whose client_id was already written isn't written again
+        if let Some(client_id) = &payload.client_id
+            && client_id_received(&state, payload.execution_id, client_id).await?
+        {
+            let response = CreateTestResultResponse {
+                status: "duplicate".to_string(),
+            };
+            return Ok((StatusCode::OK, Json(response)));
+        }
+
+        // Enqueue the result to be processed by the background writer
+        state.writer_manager.enqueue(WriterName::Main, Box::new(payload)).await.map_err(AppError::Overloaded)?;
+
+        let response = CreateTestResultResponse {
+            status: "delivered".to_string(),
+        };
+        Ok((StatusCode::CREATED, Json(response)))
+    }).await
 }
 
 async fn get_test_result(
     Path(id): Path<i64>,
     State(state): State<AppState>,
-) -> Result<Json<TestResult>, (StatusCode, String)> {
-    let mut conn = state.pool.acquire().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+) -> Result<Json<TestResult>, AppError> {
+    let mut conn = state.pool.acquire().await?;
     
-    let test_result = sqlx::query_as::<_, TestResult>(
-        "SELECT * FROM test_result WHERE id = ?"
+    let mut test_result = sqlx::query_as::<_, TestResult>(
+        &format!("SELECT {} FROM test_result WHERE id = ?", TEST_RESULT_COLUMNS)
     )
     .bind(id)
     .fetch_optional(&mut *conn)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-    .ok_or((StatusCode::NOT_FOUND, "Test result not found".to_string()))?;
+    .await?
+    .ok_or(AppError::NotFound("Test result not found".to_string()))?;
+
+    // Offloaded logs are returned like inline ones
+    test_result.log = full_log(&mut conn, id, test_result.log.take()).await?;
+    test_result.steps = Some(list_test_steps(&mut conn, id).await?);
+    assign_teams(&mut conn, &state.ownership, std::slice::from_mut(&mut test_result)).await?;
     
     Ok(Json(test_result))
 }
 
+/// The log of a test result as plain text, whole or a part of it:
+/// the last `tail` lines, or `limit` bytes from `offset`. A part is answered with 206 and `Content-Range`.
+async fn get_test_result_log(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, AppError> {
+    let tail = size_of(&params, "tail")?;
+    let offset = size_of(&params, "offset")?;
+    let limit = size_of(&params, "limit")?;
+    if tail.is_some() && (offset.is_some() || limit.is_some()) {
+        return Err(AppError::BadRequest("tail can't be combined with offset or limit".to_string()));
+    }
+
+    let mut conn = state.pool.acquire().await?;
+
+    let inline = sqlx::query_scalar::<_, Option<String>>("SELECT log FROM test_result WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound("Test result not found".to_string()))?;
+    let log = full_log(&mut conn, id, inline)
+        .await?
+        .ok_or(AppError::NotFound("Test result has no log".to_string()))?;
+
+    let size = log.len();
+    if offset.is_some_and(|offset| offset > 0 && offset >= size) {
+        return Err(AppError::RangeNotSatisfiable(size));
+    }
+    let (start, end) = match tail {
+        Some(lines) => (tail_start(&log, lines), size),
+        None => {
+            let start = offset.unwrap_or(0);
+            (start, limit.map_or(size, |limit| start.saturating_add(limit).min(size)))
+        }
+    };
+
+    let body = String::from_utf8_lossy(&log.as_bytes()[start..end]).into_owned();
+    let content_type = (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string());
+    if start == 0 && end == size {
+        return Ok(([content_type], body).into_response());
+    }
+
+    let content_range = if start < end {
+        format!("bytes {}-{}/{}", start, end - 1, size)
+    } else {
+        format!("bytes */{}", size)
+    };
+    Ok((StatusCode::PARTIAL_CONTENT, [content_type, (header::CONTENT_RANGE, content_range)], body).into_response())
+}
+
+/// Byte offset where the last `lines` lines of the log start, a trailing line break doesn't start a line
+fn tail_start(log: &str, lines: usize) -> usize {
+    if lines == 0 {
+        return log.len();
+    }
+    log.strip_suffix('\n')
+        .unwrap_or(log)
+        .rmatch_indices('\n')
+        .nth(lines - 1)
+        .map_or(0, |(index, _)| index + 1)
+}
+
+fn size_of(params: &HashMap<String, String>, key: &str) -> Result<Option<usize>, AppError> {
+    params.get(key)
+        .map(|value| value.trim().parse::<usize>())
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid {}: {}", key, e)))
+}
+
 async fn update_test_result_status(
     Path(id): Path<i64>,
     State(state): State<AppState>,
-    Json(payload): Json<UpdateStatusRequest>,
-) -> Result<StatusCode, (StatusCode, String)> {
+    ValidJson(payload): ValidJson<UpdateStatusRequest>,
+) -> Result<StatusCode, AppError> {
     // Convert the string status to the Status enum using the value_of function
     let status = Status::value_of(&payload.status)
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        .map_err(AppError::BadRequest)?;
 
-    let mut conn = state.pool.acquire().await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut conn = state.pool.acquire().await?;
 
-    match db_update_test_result_status(&mut *conn, id, &status).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT), // 204 No Content
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
-    }
+    db_update_test_result_status(&mut *conn, id, &status).await?;
+    Ok(StatusCode::NO_CONTENT) // 204 No Content
 }
 
@@ -0,0 +1,117 @@
+// src/routes/search.rs
+// Define full-text search API over test results here
+
+use axum::{
+    extract::{Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use sqlx::{QueryBuilder, Sqlite};
+use std::collections::HashMap;
+
+use super::filter::{ids_of, ResultFilter};
+use super::pagination::limit_of;
+use crate::error::AppError;
+use crate::models::{SearchHit, SearchResponse};
+use crate::state::AppState;
+
+// Markers wrapped around matched terms in highlights and snippets
+const HIGHLIGHT_START: &str = "<mark>";
+const HIGHLIGHT_END: &str = "</mark>";
+const SNIPPET_ELLIPSIS: &str = "...";
+const SNIPPET_TOKENS: i64 = 16;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/results/search", get(search_test_results))
+}
+
+/// Turn free text into an FTS5 query.
+/// Every term is quoted so that user input never hits the FTS5 query syntax,
+/// a trailing `*` is kept as a prefix search, and terms are implicitly AND-ed.
+fn to_fts_query(q: &str) -> String {
+    q.split_whitespace()
+        .filter_map(|term| {
+            let (term, prefix) = match term.strip_suffix('*') {
+                Some(stripped) => (stripped, "*"),
+                None => (term, ""),
+            };
+            if term.is_empty() {
+                return None;
+            }
+            Some(format!("\"{}\"{}", term.replace('"', "\"\""), prefix))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+async fn search_test_results(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<SearchResponse>, AppError> {
+    let query = params.get("q").map(|s| s.trim().to_string()).unwrap_or_default();
+    let fts_query = to_fts_query(&query);
+    if fts_query.is_empty() {
+        return Err(AppError::BadRequest("Query parameter q is required".to_string()));
+    }
+
+    let mut conn = state.pool.acquire().await?;
+
+    let limit = limit_of(&params)?;
+    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let execution_ids = ids_of(&params, "execution_id")?;
+    let filter = ResultFilter::from_params(&params)?;
+
+    let push_conditions = |builder: &mut QueryBuilder<'_, Sqlite>| {
+        builder.push(" FROM test_result_fts JOIN test_result r ON r.id = test_result_fts.rowid WHERE test_result_fts MATCH ");
+        builder.push_bind(fts_query.clone());
+
+        if !execution_ids.is_empty() {
+            builder.push(" AND r.execution_id IN (");
+            let mut separated = builder.separated(", ");
+            for execution_id in &execution_ids {
+                separated.push_bind(*execution_id);
+            }
+            separated.push_unseparated(")");
+        }
+
+        filter.push_conditions_of("r", builder);
+    };
+
+    // Fetch total count
+    let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*)");
+    push_conditions(&mut count_query);
+    let total = count_query.build_query_scalar::<i64>().fetch_one(&mut *conn).await?;
+
+    // Fetch items
+    let mut items_query = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT r.id, r.execution_id, r.name, r.platform, r.status, r.time_created, \
+         highlight(test_result_fts, 0, '{start}', '{end}') AS name_highlight, \
+         snippet(test_result_fts, -1, '{start}', '{end}', '{ellipsis}', {tokens}) AS snippet",
+        start = HIGHLIGHT_START,
+        end = HIGHLIGHT_END,
+        ellipsis = SNIPPET_ELLIPSIS,
+        tokens = SNIPPET_TOKENS,
+    ));
+    push_conditions(&mut items_query);
+    items_query.push(" ORDER BY bm25(test_result_fts) LIMIT ");
+    items_query.push_bind(limit);
+    items_query.push(" OFFSET ");
+    items_query.push_bind(offset);
+    let items = items_query.build_query_as::<SearchHit>().fetch_all(&mut *conn).await?;
+
+    let has_next = (offset + limit) < total;
+
+    let response = SearchResponse {
+        query,
+        total,
+        limit,
+        offset,
+        has_next,
+        items,
+    };
+
+    Ok(Json(response))
+}
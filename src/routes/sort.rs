@@ -0,0 +1,163 @@
+// src/routes/sort.rs
+// Whitelisted sort orders of listings, with the keyset condition to continue after a cursor
+
+use sqlx::{QueryBuilder, Sqlite};
+use std::collections::HashMap;
+
+use super::pagination::{Cursor, SortKey};
+use crate::error::AppError;
+use crate::models::{Execution, TestResult};
+
+/// A sortable field: the name used in `sort=` and its SQL expression.
+/// Expressions never evaluate to NULL, so that they can be compared with the cursor.
+pub struct SortField {
+    name: &'static str,
+    expr: &'static str,
+}
+
+pub const EXECUTION_SORT_FIELDS: &[SortField] = &[
+    SortField { name: "id", expr: "id" },
+    SortField { name: "time_created", expr: "time_created" },
+    SortField { name: "time_received", expr: "time_received" },
+    SortField { name: "name", expr: "name" },
+];
+
+pub const RESULT_SORT_FIELDS: &[SortField] = &[
+    SortField { name: "id", expr: "id" },
+    SortField { name: "time_created", expr: "time_created" },
+    SortField { name: "time_received", expr: "time_received" },
+    SortField { name: "name", expr: "name" },
+    SortField { name: "status", expr: "status" },
+    SortField { name: "execution_time", expr: "COALESCE(execution_time, -1)" },
+    SortField { name: "counter", expr: "counter" },
+    SortField { name: "platform", expr: "platform" },
+    // failed and errored results first
+    SortField { name: "failures_first", expr: "CASE WHEN status IN ('F', 'E') THEN 0 ELSE 1 END" },
+];
+
+/// Items listed with a sort, they provide the value of every field they can be sorted by
+pub trait Sortable {
+    fn id(&self) -> Option<i64>;
+    fn sort_key(&self, field: &str) -> SortKey;
+}
+
+impl Sortable for Execution {
+    fn id(&self) -> Option<i64> {
+        self.id
+    }
+
+    fn sort_key(&self, field: &str) -> SortKey {
+        match field {
+            "time_created" => SortKey::Int(self.time_created),
+            "time_received" => SortKey::Int(self.time_received),
+            "name" => SortKey::Text(self.name.clone()),
+            _ => SortKey::Int(self.id.unwrap_or(0)),
+        }
+    }
+}
+
+impl Sortable for TestResult {
+    fn id(&self) -> Option<i64> {
+        self.id
+    }
+
+    fn sort_key(&self, field: &str) -> SortKey {
+        match field {
+            "time_created" => SortKey::Int(self.time_created),
+            "time_received" => SortKey::Int(self.time_received),
+            "name" => SortKey::Text(self.name.clone()),
+            "status" => SortKey::Text(self.status.code().to_string()),
+            "execution_time" => SortKey::Int(self.execution_time.unwrap_or(-1)),
+            "counter" => SortKey::Int(self.counter),
+            "platform" => SortKey::Text(self.platform.clone()),
+            "failures_first" => SortKey::Int(if self.status.is_failure() { 0 } else { 1 }),
+            _ => SortKey::Int(self.id.unwrap_or(0)),
+        }
+    }
+}
+
+/// Sort order of a listing: one field and a direction, ties are broken by id in the same direction
+pub struct Sort {
+    field: &'static SortField,
+    descending: bool,
+}
+
+impl Sort {
+    /// Read `sort=<field>` or `sort=<field>:<asc|desc>`, the field defaults to id
+    pub fn from_params(
+        params: &HashMap<String, String>,
+        fields: &'static [SortField],
+        default_descending: bool,
+    ) -> Result<Self, AppError> {
+        let Some(sort) = params.get("sort").filter(|s| !s.is_empty()) else {
+            return Ok(Sort { field: &fields[0], descending: default_descending });
+        };
+
+        let (name, direction) = sort.split_once(':').unwrap_or((sort.as_str(), "asc"));
+        let field = fields.iter().find(|field| field.name == name).ok_or_else(|| {
+            let names: Vec<&str> = fields.iter().map(|field| field.name).collect();
+            AppError::BadRequest(format!("Invalid sort field: {}. Expected one of {}", name, names.join(", ")))
+        })?;
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            _ => return Err(AppError::BadRequest(format!("Invalid sort direction: {}. Expected asc or desc", direction))),
+        };
+
+        Ok(Sort { field, descending })
+    }
+
+    fn name(&self) -> String {
+        format!("{}:{}", self.field.name, if self.descending { "desc" } else { "asc" })
+    }
+
+    fn is_id(&self) -> bool {
+        self.field.name == "id"
+    }
+
+    /// Append the keyset condition which continues after the cursor
+    pub fn push_after(&self, builder: &mut QueryBuilder<'_, Sqlite>, cursor: &Cursor) -> Result<(), AppError> {
+        let operator = if self.descending { "<" } else { ">" };
+
+        // cursors handed out without a sort come from the default id order
+        if self.is_id() && cursor.sort.is_none() {
+            builder.push(format!(" AND id {} ", operator));
+            builder.push_bind(cursor.id);
+            return Ok(());
+        }
+
+        if cursor.sort.as_deref() != Some(self.name().as_str()) {
+            return Err(AppError::BadRequest(format!("The cursor doesn't belong to sort {}", self.name())));
+        }
+        let key = cursor.key.clone()
+            .ok_or(AppError::BadRequest("The cursor has no sort value".to_string()))?;
+
+        builder.push(format!(" AND ({}, id) {} (", self.field.expr, operator));
+        match key {
+            SortKey::Int(value) => builder.push_bind(value),
+            SortKey::Text(value) => builder.push_bind(value),
+        };
+        builder.push(", ");
+        builder.push_bind(cursor.id);
+        builder.push(")");
+        Ok(())
+    }
+
+    pub fn push_order_by(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        if self.is_id() {
+            builder.push(format!(" ORDER BY id {}", direction));
+        } else {
+            builder.push(format!(" ORDER BY {} {}, id {}", self.field.expr, direction, direction));
+        }
+    }
+
+    /// Cursor continuing after the item
+    pub fn cursor_of<T: Sortable>(&self, item: &T) -> Option<Cursor> {
+        Some(Cursor {
+            id: item.id()?,
+            sort: Some(self.name()),
+            key: Some(item.sort_key(self.field.name)),
+        })
+    }
+}
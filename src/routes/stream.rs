@@ -2,19 +2,28 @@
 // Define stream API here
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::{FromRequest, Path, Request, State},
+    http::{HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Json, Response},
     routing::post,
     Router,
 };
-use axum::body::Body;
-use futures::{StreamExt, TryStreamExt};
+use axum::body::{Body, Bytes};
+use futures::stream::Peekable;
+use futures::{Stream, StreamExt, TryStreamExt};
+use std::pin::Pin;
 use futures::AsyncBufReadExt;
 
+use crate::error::AppError;
 use crate::{background::writer::WriterName, database::check_execution_existing};
-use crate::models::{CreateTestResult, StreamResponse, FailedItem};
+use super::execution::insert_execution;
+use super::idempotency::{client_id_received, idempotency_key, idempotent, line_client_id, RequestBody, StreamedBody};
+use crate::models::{CreateTestResult, CreateTestResultBase, StreamHeader, StreamResponse, FailedItem};
 use crate::state::AppState;
+use crate::validation;
+
+// Path segment in place of the execution id when the header line of the stream creates the execution
+const NEW_EXECUTION: &str = "new";
 
 // Status constants for stream response
 const STATUS_COMPLETED: &str = "C"; // Completed
@@ -34,83 +43,216 @@ fn to_status(failed: i64, enqueued: i64) -> &'static str {
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/api/executions/:execution_id/result/stream", post(stream_test_results))
+        // `:` can't follow a static prefix within a segment, so the action is matched by the handler
+        .route("/api/executions/:execution_id/:action", post(batch_test_results))
 }
 
-async fn stream_test_results(
-    Path(execution_id): Path<i64>,
-    State(state): State<AppState>,
-    body: Body,
-) -> Result<Json<StreamResponse>, (StatusCode, String)> {
-    // Check if the execution exists
-    {
-        let mut conn = state.pool.acquire().await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        if !check_execution_existing(&mut *conn, execution_id).await {
-            let error_message = format!("Invalid execution_id [{}], no execution is found.", execution_id);
-            return Err((StatusCode::BAD_REQUEST, error_message));
+/// Counters of one ingestion request, shared by the stream and the batch endpoints
+#[derive(Default)]
+struct Ingestion {
+    received: i64,
+    enqueued: i64,
+    duplicates: i64,
+    failed: i64,
+    failed_items: Vec<FailedItem>,
+    // the Idempotency-Key of the request, its lines without client_id get one derived from it
+    idempotency_key: Option<String>,
+}
+
+impl Ingestion {
+    fn new(headers: &HeaderMap) -> Self {
+        Ingestion {
+            idempotency_key: idempotency_key(headers),
+            ..Default::default()
         }
     }
 
-    let stream = body
-        .into_data_stream()
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
-        .into_async_read()
-        .lines();
-    
-    futures::pin_mut!(stream);
-    
-    let mut received = 0;
-    let mut enqueued = 0;
-    let mut failed = 0;
-    let mut failed_items = Vec::new();
-    
-    while let Some(line_result) = stream.next().await {
-        received += 1;
-        
-        match line_result {
-            Ok(line) => {
-                let payload: Result<CreateTestResult, _> = CreateTestResult::from_json(&line, execution_id);
-                
-                match payload {
-                    Ok(payload) => {
-                        // Enqueue the result to be processed by the background writer
-                        match state.writer_manager.enqueue(WriterName::Main, Box::new(payload)).await {
-                            Ok(_) => enqueued += 1,
-                            Err(e) => {
-                                failed += 1;
-                                failed_items.push(FailedItem {
-                                    error: e,
-                                    raw_payload: Some(line)
-                                });
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        failed += 1;
-                        failed_items.push(FailedItem {
-                            error: e.to_string(),
-                            raw_payload: Some(line)
-                        });
-                    }
-                }
+    /// Validate one item and enqueue it to be processed by the background writer
+    async fn ingest(
+        &mut self,
+        state: &AppState,
+        execution_id: i64,
+        item: Result<String, String>,
+    ) {
+        let index = self.received;
+        self.received += 1;
+
+        let line = match item {
+            Ok(line) => line,
+            Err(error) => {
+                self.fail(index, error, None, None);
+                return;
             }
+        };
+
+        let mut payload = match validation::parse::<CreateTestResultBase>(&line) {
+            Ok(base) => CreateTestResult { execution_id, base },
             Err(e) => {
-                failed += 1;
-                failed_items.push(FailedItem {
-                    error: e.to_string(),
-                    raw_payload: None
-                });
+                self.fail(index, e.message, e.field, Some(line));
+                return;
             }
+        };
+        if payload.client_id.is_none() {
+            payload.base.client_id = self.idempotency_key.as_deref().map(|key| line_client_id(key, index, &line));
         }
+
+        // A line whose client_id was already written is a duplicate, it isn't written again
+        if let Some(client_id) = &payload.client_id {
+            match client_id_received(state, execution_id, client_id).await {
+                Ok(false) => {}
+                Ok(true) => {
+                    self.duplicates += 1;
+                    return;
+                }
+                Err(e) => {
+                    self.fail(index, e.to_string(), None, Some(line));
+                    return;
+                }
+            }
+        }
+
+        match state.writer_manager.enqueue(WriterName::Main, Box::new(payload)).await {
+            Ok(_) => self.enqueued += 1,
+            Err(e) => self.fail(index, e, None, Some(line)),
+        }
+    }
+
+    fn fail(&mut self, index: i64, error: String, field: Option<String>, raw_payload: Option<String>) {
+        self.failed += 1;
+        self.failed_items.push(FailedItem {
+            index,
+            error,
+            field,
+            raw_payload,
+        });
+    }
+
+    fn into_response(self, execution_id: i64) -> StreamResponse {
+        StreamResponse {
+            status: to_status(self.failed, self.enqueued + self.duplicates).to_string(),
+            execution_id,
+            received: self.received,
+            inserted: self.enqueued,
+            duplicates: self.duplicates,
+            failed: self.failed,
+            failed_items: if self.failed > 0 { Some(self.failed_items) } else { None },
+        }
+    }
+}
+
+async fn check_execution(state: &AppState, execution_id: i64) -> Result<(), AppError> {
+    let mut conn = state.pool.acquire().await?;
+    if !check_execution_existing(&mut *conn, execution_id).await {
+        let error_message = format!("Invalid execution_id [{}], no execution is found.", execution_id);
+        return Err(AppError::BadRequest(error_message));
     }
-    
-    let response = StreamResponse {
-        status: to_status(failed, enqueued).to_string(),
-        execution_id,
-        received,
-        inserted: enqueued,
-        failed,
-        failed_items: if failed > 0 { Some(failed_items) } else { None },
+    Ok(())
+}
+
+/// Stream the test results of an execution. The stream may start with a header line creating the execution,
+/// then `new` takes the place of the execution id in the path.
+async fn stream_test_results(
+    Path(execution): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    uri: Uri,
+    body: Body,
+) -> Result<(StatusCode, Json<StreamResponse>), AppError> {
+    let body = StreamedBody::new(body);
+    idempotent(&state, &headers, &uri, RequestBody::Streamed(body.clone()), async {
+        let stream = lines_of(body.chunks()).peekable();
+        futures::pin_mut!(stream);
+
+        let header = header_of(stream.as_mut()).await?;
+        let execution_id = match (execution.as_str(), header) {
+            (NEW_EXECUTION, Some(header)) => insert_execution(&state, &header.execution).await?.id.unwrap_or_default(),
+            (NEW_EXECUTION, None) => {
+                return Err(AppError::BadRequest("A stream to a new execution must start with a header line".to_string()));
+            }
+            (_, Some(_)) => {
+                return Err(AppError::BadRequest(format!(
+                    "A header line creates the execution, post the stream to /api/executions/{}/result/stream",
+                    NEW_EXECUTION
+                )));
+            }
+            (id, None) => {
+                let execution_id = id
+                    .parse::<i64>()
+                    .map_err(|_| AppError::BadRequest(format!("Invalid execution_id [{}]", id)))?;
+                check_execution(&state, execution_id).await?;
+                execution_id
+            }
+        };
+
+        let ingestion = ingest_lines(&state, &headers, execution_id, &mut stream).await;
+
+        Ok((StatusCode::OK, Json(ingestion.into_response(execution_id))))
+    }).await
+}
+
+/// The header line of a stream, a JSON object with an `execution` member. Other first lines are left to be test results.
+async fn header_of(
+    mut lines: Pin<&mut Peekable<impl Stream<Item = std::io::Result<String>>>>,
+) -> Result<Option<StreamHeader>, AppError> {
+    let is_header = match lines.as_mut().peek().await {
+        Some(Ok(line)) => serde_json::from_str::<serde_json::Value>(line)
+            .is_ok_and(|value| value.get("execution").is_some()),
+        _ => false,
     };
-    
-    Ok(Json(response))
+    if !is_header {
+        return Ok(None);
+    }
+
+    let line = lines.next().await.and_then(Result::ok).unwrap_or_default();
+    Ok(Some(validation::parse::<StreamHeader>(&line)?))
+}
+
+fn lines_of(chunks: impl Stream<Item = Result<Bytes, axum::Error>>) -> impl Stream<Item = std::io::Result<String>> {
+    chunks
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+        .into_async_read()
+        .lines()
+}
+
+async fn ingest_lines(
+    state: &AppState,
+    headers: &HeaderMap,
+    execution_id: i64,
+    lines: &mut (impl Stream<Item = std::io::Result<String>> + Unpin),
+) -> Ingestion {
+    let mut ingestion = Ingestion::new(headers);
+    while let Some(line_result) = lines.next().await {
+        ingestion.ingest(state, execution_id, line_result.map_err(|e| e.to_string())).await;
+    }
+    ingestion
+}
+
+/// Ingest a JSON array of test results, elements are validated one by one like the lines of a stream
+async fn batch_test_results(
+    Path((execution_id, action)): Path<(i64, String)>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    uri: Uri,
+    request: Request,
+) -> Result<(StatusCode, Json<StreamResponse>), Response> {
+    if action != "results:batch" {
+        return Err(AppError::NotFound(format!("Unknown action: {}", action)).into_response());
+    }
+
+    // the body is read once the action is known, an unknown action is 404 whatever its body
+    let Json(items) = Json::<Vec<serde_json::Value>>::from_request(request, &state)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    let body = RequestBody::parsed(&items).map_err(IntoResponse::into_response)?;
+    idempotent(&state, &headers, &uri, body, async {
+        check_execution(&state, execution_id).await?;
+
+        let mut ingestion = Ingestion::new(&headers);
+        for item in items {
+            ingestion.ingest(&state, execution_id, Ok(item.to_string())).await;
+        }
+
+        Ok((StatusCode::OK, Json(ingestion.into_response(execution_id))))
+    }).await.map_err(IntoResponse::into_response)
 }
@@ -0,0 +1,132 @@
+// src/routes/test_case.rs
+// Define test case catalog API here
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use std::collections::HashMap;
+
+use super::pagination::limit_of;
+use crate::error::AppError;
+use crate::models::{TestCase, TestCaseListResponse, UpdateTestCase};
+use crate::routes::filter::escape_like;
+use crate::state::AppState;
+use crate::validation::ValidJson;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/test-cases", get(get_test_cases))
+        .route("/api/test-case/:id", get(get_test_case).patch(update_test_case))
+}
+
+async fn get_test_cases(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<TestCaseListResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let limit = limit_of(&params)?;
+    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let mut conditions = "WHERE 1 = 1".to_string();
+    let mut bindings: Vec<String> = Vec::new();
+
+    // Add filters
+    if let Some(name) = params.get("name") {
+        conditions.push_str(" AND name = ?");
+        bindings.push(name.clone());
+    }
+
+    if let Some(name_prefix) = params.get("name_prefix") {
+        conditions.push_str(" AND name LIKE ? ESCAPE '\\'");
+        bindings.push(format!("{}%", escape_like(name_prefix)));
+    }
+
+    for column in ["platform", "owner", "component"] {
+        if let Some(value) = params.get(column) {
+            conditions.push_str(&format!(" AND {} = ?", column));
+            bindings.push(value.clone());
+        }
+    }
+
+    let count_query = format!("SELECT COUNT(*) FROM test_case {}", conditions);
+    let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+    for binding in &bindings {
+        count_query_builder = count_query_builder.bind(binding);
+    }
+    let total = count_query_builder.fetch_one(&mut *conn).await?;
+
+    let query = format!("SELECT * FROM test_case {} ORDER BY name, platform LIMIT ? OFFSET ?", conditions);
+    let mut query_builder = sqlx::query_as::<_, TestCase>(&query);
+    for binding in &bindings {
+        query_builder = query_builder.bind(binding);
+    }
+    query_builder = query_builder.bind(limit).bind(offset);
+    let items = query_builder.fetch_all(&mut *conn).await?;
+
+    let has_next = (offset + limit) < total;
+
+    let response = TestCaseListResponse {
+        total,
+        limit,
+        offset,
+        has_next,
+        items,
+    };
+
+    Ok(Json(response))
+}
+
+async fn get_test_case(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<TestCase>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let test_case = sqlx::query_as::<_, TestCase>("SELECT * FROM test_case WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound("Test case not found".to_string()))?;
+
+    Ok(Json(test_case))
+}
+
+/// Curate a test case, the writer only fills the description and suite path
+async fn update_test_case(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<UpdateTestCase>,
+) -> Result<Json<TestCase>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    // each field is bound as (given, value), a field given as null is cleared
+    let test_case = sqlx::query_as::<_, TestCase>(
+        r#"
+        UPDATE test_case
+        SET description = CASE WHEN ? THEN ? ELSE description END,
+            owner = CASE WHEN ? THEN ? ELSE owner END,
+            component = CASE WHEN ? THEN ? ELSE component END,
+            suite_path = CASE WHEN ? THEN ? ELSE suite_path END
+        WHERE id = ?
+        RETURNING *
+        "#
+    )
+    .bind(payload.description.is_some())
+    .bind(payload.description.as_ref().and_then(Option::as_deref))
+    .bind(payload.owner.is_some())
+    .bind(payload.owner.as_ref().and_then(Option::as_deref))
+    .bind(payload.component.is_some())
+    .bind(payload.component.as_ref().and_then(Option::as_deref))
+    .bind(payload.suite_path.is_some())
+    .bind(payload.suite_path.as_ref().and_then(Option::as_deref))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound("Test case not found".to_string()))?;
+
+    Ok(Json(test_case))
+}
@@ -0,0 +1,158 @@
+// src/routes/webhook.rs
+// Define webhook registration API and delivery log API here
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::{get, post},
+    Router,
+};
+use sqlx::types::Json as SqlJson;
+use std::collections::HashMap;
+
+use super::pagination::limit_of;
+use crate::error::AppError;
+use crate::models::{CreateWebhook, Webhook, WebhookDelivery, WebhookDeliveryListResponse, WebhookListResponse};
+use crate::state::AppState;
+use crate::validation::ValidJson;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/webhook", post(create_webhook))
+        .route("/api/webhooks", get(get_webhooks))
+        .route("/api/webhook/:id", get(get_webhook).delete(delete_webhook))
+        .route("/api/webhook/:id/deliveries", get(get_deliveries))
+}
+
+async fn create_webhook(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<CreateWebhook>,
+) -> Result<(StatusCode, Json<Webhook>), AppError> {
+    let mut conn = state.pool.acquire().await?;
+    let webhook = sqlx::query_as::<_, Webhook>(
+        r#"
+        INSERT INTO webhook (url, secret, events, min_pass_rate, time_created)
+        VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+        RETURNING *
+        "#
+    )
+    .bind(payload.url.trim())
+    .bind(&payload.secret)
+    .bind(SqlJson(&payload.events))
+    .bind(payload.min_pass_rate)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+/// The registered webhooks, their secrets are never returned
+async fn get_webhooks(State(state): State<AppState>) -> Result<Json<WebhookListResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let items = sqlx::query_as::<_, Webhook>("SELECT * FROM webhook ORDER BY id")
+        .fetch_all(&mut *conn)
+        .await?;
+
+    Ok(Json(WebhookListResponse { items }))
+}
+
+async fn get_webhook(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<Webhook>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let webhook = sqlx::query_as::<_, Webhook>("SELECT * FROM webhook WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound("Webhook not found".to_string()))?;
+
+    Ok(Json(webhook))
+}
+
+/// Delete a webhook with its deliveries, the pending ones are not sent anymore
+async fn delete_webhook(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query("DELETE FROM webhook_delivery WHERE webhook_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    let deleted = sqlx::query("DELETE FROM webhook WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if deleted == 0 {
+        return Err(AppError::NotFound("Webhook not found".to_string()));
+    }
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The deliveries of a webhook, latest first, optionally filtered by status
+async fn get_deliveries(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<WebhookDeliveryListResponse>, AppError> {
+    let mut conn = state.pool.acquire().await?;
+
+    let exists = sqlx::query_scalar::<_, i64>("SELECT id FROM webhook WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::NotFound("Webhook not found".to_string()));
+    }
+
+    let limit = limit_of(&params)?;
+    let offset: i64 = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let mut conditions = "WHERE webhook_id = ?".to_string();
+    let mut bindings: Vec<String> = Vec::new();
+
+    if let Some(status) = params.get("status") {
+        conditions.push_str(" AND status = ?");
+        bindings.push(status.clone());
+    }
+
+    if let Some(event) = params.get("event") {
+        conditions.push_str(" AND event = ?");
+        bindings.push(event.clone());
+    }
+
+    let count_query = format!("SELECT COUNT(*) FROM webhook_delivery {}", conditions);
+    let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query).bind(id);
+    for binding in &bindings {
+        count_query_builder = count_query_builder.bind(binding);
+    }
+    let total = count_query_builder.fetch_one(&mut *conn).await?;
+
+    let query = format!("SELECT * FROM webhook_delivery {} ORDER BY id DESC LIMIT ? OFFSET ?", conditions);
+    let mut query_builder = sqlx::query_as::<_, WebhookDelivery>(&query).bind(id);
+    for binding in &bindings {
+        query_builder = query_builder.bind(binding);
+    }
+    query_builder = query_builder.bind(limit).bind(offset);
+    let items = query_builder.fetch_all(&mut *conn).await?;
+
+    let has_next = (offset + limit) < total;
+
+    Ok(Json(WebhookDeliveryListResponse {
+        total,
+        limit,
+        offset,
+        has_next,
+        items,
+    }))
+}
@@ -0,0 +1,133 @@
+// src/signature.rs
+// Failure signature: normalize failure logs so that identical failures share one hash
+
+// Lines mentioning one of these words are considered to describe the failure
+const FAILURE_KEYWORDS: [&str; 8] = [
+    "error", "exception", "fail", "assert", "panic", "timeout", "timed out", "refused",
+];
+
+// Upper bound of failure lines taken into the signature, stack traces are cut here
+const MAX_SIGNATURE_LINES: usize = 5;
+
+// Hex tokens at least this long are treated as ids (hashes, uuids, object ids, ...)
+const MIN_HEX_ID_LEN: usize = 4;
+
+// Dash-joined placeholders (uuids, dates) are merged into a single id
+const JOINED_PLACEHOLDERS: [&str; 4] = ["<n>-<n>", "<n>-<id>", "<id>-<n>", "<id>-<id>"];
+
+/// Compute the signature hash of a failure log.
+/// Returns None if the log has no content to build a signature from.
+pub fn failure_signature(log: &str) -> Option<String> {
+    let normalized = normalize_failure_log(log);
+    if normalized.is_empty() {
+        return None;
+    }
+    Some(format!("{:016x}", fnv1a_64(normalized.as_bytes())))
+}
+
+/// Reduce a failure log to the lines describing the failure, stripped of volatile parts.
+///
+/// Lines containing a failure keyword are kept (or the last non-empty line when there is none),
+/// then numbers, ids, timestamps and paths are replaced by placeholders.
+pub fn normalize_failure_log(log: &str) -> String {
+    let lines: Vec<&str> = log.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+
+    let mut failure_lines: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            FAILURE_KEYWORDS.iter().any(|keyword| lower.contains(keyword))
+        })
+        .take(MAX_SIGNATURE_LINES)
+        .collect();
+
+    if failure_lines.is_empty() {
+        failure_lines.extend(lines.last());
+    }
+
+    failure_lines
+        .iter()
+        .map(|line| normalize_line(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn normalize_line(line: &str) -> String {
+    line.split_whitespace()
+        .map(normalize_word)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize_word(word: &str) -> String {
+    // paths and urls
+    if word.contains('/') || word.contains('\\') {
+        return "<path>".to_string();
+    }
+
+    // split the word into alphanumeric tokens and keep the separators as they are
+    let mut normalized = String::with_capacity(word.len());
+    let mut token = String::new();
+    for c in word.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            token.push(c);
+        } else {
+            normalized.push_str(&normalize_token(&token));
+            token.clear();
+            normalized.push(c);
+        }
+    }
+    normalized.push_str(&normalize_token(&token));
+
+    while let Some(joined) = JOINED_PLACEHOLDERS.iter().find(|joined| normalized.contains(*joined)) {
+        normalized = normalized.replace(joined, "<id>");
+    }
+
+    normalized.to_lowercase()
+}
+
+fn normalize_token(token: &str) -> String {
+    if token.is_empty() {
+        return String::new();
+    }
+
+    let has_digit = token.chars().any(|c| c.is_ascii_digit());
+    if !has_digit {
+        return token.to_string();
+    }
+
+    if token.chars().all(|c| c.is_ascii_digit()) {
+        return "<n>".to_string();
+    }
+
+    if token.len() >= MIN_HEX_ID_LEN && token.chars().all(|c| c.is_ascii_hexdigit()) {
+        return "<id>".to_string();
+    }
+
+    // mixed tokens such as `worker7` or `12ms`: collapse every digit run
+    let mut collapsed = String::with_capacity(token.len());
+    let mut in_digits = false;
+    for c in token.chars() {
+        if c.is_ascii_digit() {
+            if !in_digits {
+                collapsed.push_str("<n>");
+            }
+            in_digits = true;
+        } else {
+            collapsed.push(c);
+            in_digits = false;
+        }
+    }
+    collapsed
+}
+
+/// 64-bit FNV-1a, stable across builds so that stored signatures stay comparable
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
@@ -6,6 +6,7 @@ use sqlx::SqlitePool;
 use crate::auth::{self, AuthProvider};
 use crate::config::Config;
 use crate::{config, database, suggestion};
+use crate::ownership::OwnershipRules;
 use crate::background::writer::{DefaultWriter, Writer, WriterManager, WriterName};
 
 
@@ -16,6 +17,9 @@ pub struct AppState {
     pub writer_manager: Arc<WriterManager>,
     pub auth_provider: Option<Arc<AuthProvider>>,
     pub execution_prefix_trie: Option<Arc<parking_lot::RwLock<suggestion::ExecutionPrefixTrie>>>,
+    pub ownership: Arc<parking_lot::RwLock<OwnershipRules>>,
+    // Held while a rule change is stored, the rules are loaded and swapped, so that changes apply in order
+    pub ownership_reload: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl AppState {
@@ -34,6 +38,9 @@ pub async fn init_state() -> Result<AppState, Box<dyn std::error::Error>> {
     // Initialize SQLite connection pools
     let (main_pool, writer_pool) = database::init_db(&config).await?;
 
+    // Compile the ownership rules of the config and of the admin API
+    let ownership = OwnershipRules::load(&mut *main_pool.acquire().await?, &config.ownership.rules).await?;
+
     // Initialize writer manager
     let mut writer_manager = WriterManager::new();
     let default_writer = DefaultWriter::new(&config, writer_pool).await;
@@ -65,5 +72,7 @@ pub async fn init_state() -> Result<AppState, Box<dyn std::error::Error>> {
         writer_manager: Arc::new(writer_manager),
         auth_provider,
         execution_prefix_trie,
+        ownership: Arc::new(parking_lot::RwLock::new(ownership)),
+        ownership_reload: Arc::new(tokio::sync::Mutex::new(())),
     })
 }
@@ -91,7 +91,7 @@ impl ExecutionPrefixTrie {
     ) -> Result<Self, sqlx::Error> {
         let mut trie = PrefixTrie::new(min_prefix_len, max_prefix_len, max_candidates);
         
-        let executions: Vec<Execution> = sqlx::query_as("SELECT * FROM execution ORDER BY time_created DESC")
+        let executions: Vec<Execution> = sqlx::query_as("SELECT * FROM execution ORDER BY time_received DESC")
             .fetch_all(pool)
             .await?;
         
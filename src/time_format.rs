@@ -0,0 +1,108 @@
+// src/time_format.rs
+// The `time_format` query parameter: timestamps of JSON responses as RFC3339 strings instead of unix time
+
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    http::{header, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::timestamp;
+
+// Fields of the responses holding a unix time in seconds
+const TIMESTAMP_FIELDS: [&str; 9] = [
+    "time_created", "time_received", "expires_at", "first_seen", "last_seen", "execution_time_created",
+    "time_finished", "next_attempt_at", "time_delivered",
+];
+
+// Larger JSON responses are passed through in unix time rather than buffered
+const MAX_FORMATTED_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+enum TimeFormat {
+    Unix,
+    Rfc3339,
+}
+
+/// Middleware to rewrite the timestamps of JSON responses with `time_format=rfc3339`, the default is `unix`.
+/// Only JSON bodies of a known size up to `MAX_FORMATTED_BODY_BYTES` are rewritten, other bodies,
+/// e.g. the NDJSON and CSV exports, are passed through without being buffered.
+pub async fn format_timestamps(req: Request<Body>, next: Next) -> Response {
+    match time_format_of(req.uri().query()) {
+        Ok(TimeFormat::Unix) => return next.run(req).await,
+        Ok(TimeFormat::Rfc3339) => {}
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next.run(req).await;
+    let size = response.body().size_hint().exact();
+    if !is_json(&response) || size.is_none_or(|size| size > MAX_FORMATTED_BODY_BYTES) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_FORMATTED_BODY_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => return AppError::internal(e).into_response(),
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    rewrite_timestamps(&mut value);
+    let body = match serde_json::to_vec(&value) {
+        Ok(body) => body,
+        Err(e) => return AppError::internal(e).into_response(),
+    };
+
+    // the length of the body changed
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn time_format_of(query: Option<&str>) -> Result<TimeFormat, AppError> {
+    let value = query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == "time_format")
+        .map(|(_, value)| value);
+
+    match value {
+        None | Some("unix") => Ok(TimeFormat::Unix),
+        Some("rfc3339") => Ok(TimeFormat::Rfc3339),
+        Some(other) => Err(AppError::BadRequest(format!("Invalid time_format {}, expected unix or rfc3339", other))),
+    }
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|essence| essence.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// Replace the integer values of `TIMESTAMP_FIELDS` at any depth
+fn rewrite_timestamps(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let rfc3339 = value
+                    .as_i64()
+                    .filter(|_| TIMESTAMP_FIELDS.contains(&key.as_str()))
+                    .and_then(timestamp::to_rfc3339);
+                match rfc3339 {
+                    Some(rfc3339) => *value = Value::String(rfc3339),
+                    None => rewrite_timestamps(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(rewrite_timestamps),
+        _ => {}
+    }
+}
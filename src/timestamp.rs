@@ -0,0 +1,57 @@
+// src/timestamp.rs
+// Timestamps are stored and returned as unix time in seconds, input may also be milliseconds or RFC3339
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{de, Deserialize, Deserializer};
+
+// Integers from this value on are read as milliseconds: in seconds it is the year 5138, in milliseconds 1973
+pub const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+const EXPECTED: &str = "a unix time in seconds or milliseconds, or an RFC3339 string";
+
+/// Unix time in seconds of an integer in seconds or milliseconds
+pub fn from_epoch(value: i64) -> i64 {
+    if value.abs() >= MILLIS_THRESHOLD {
+        value / 1000
+    } else {
+        value
+    }
+}
+
+/// Unix time in seconds of an integer in seconds or milliseconds, or of an RFC3339 string
+pub fn parse(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    if let Ok(value) = text.parse::<i64>() {
+        return Ok(from_epoch(value));
+    }
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.timestamp())
+        .map_err(|_| format!("invalid timestamp `{}`, expected {}", text, EXPECTED))
+}
+
+/// RFC3339 string in UTC of a unix time in seconds
+pub fn to_rfc3339(seconds: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(seconds, 0).map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Input {
+    Integer(i64),
+    Text(String),
+}
+
+/// Deserialize an optional timestamp in any accepted input format to unix time in seconds
+pub fn deserialize_option<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => match Input::deserialize(value) {
+            Ok(Input::Integer(value)) => Ok(Some(from_epoch(value))),
+            Ok(Input::Text(text)) => parse(&text).map(Some).map_err(de::Error::custom),
+            Err(_) => Err(de::Error::custom(format!("invalid timestamp, expected {}", EXPECTED))),
+        },
+    }
+}
@@ -0,0 +1,232 @@
+// src/validation.rs
+// Validation of request payloads, errors point at the offending field
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::ValidationError;
+use crate::models::{
+    CreateExecution, CreateOwnershipRule, CreateTestResult, CreateTestResultBase, CreateWebhook, Status, StreamHeader,
+    TestStep, UpdateStatusRequest, UpdateTestCase,
+};
+use crate::ownership::validate_rule;
+use crate::webhook::validate_webhook;
+
+// Lengths of the text columns, see the database design in README
+const MAX_NAME_LEN: usize = 32;
+const MAX_PLATFORM_LEN: usize = 32;
+const MAX_DESCRIPTION_LEN: usize = 128;
+const MAX_TAG_LEN: usize = 64;
+const MAX_CREATED_BY_LEN: usize = 32;
+const MAX_KEY_LEN: usize = 255;
+const MAX_STEP_NAME_LEN: usize = 255;
+const MAX_SUITE_PATH_LEN: usize = 255;
+const MAX_OWNER_LEN: usize = 64;
+const MAX_COMPONENT_LEN: usize = 64;
+const MAX_TEAM_LEN: usize = 64;
+const MAX_URL_LEN: usize = 2048;
+
+// Timestamps may be ahead of the server clock by this many seconds
+const MAX_CLOCK_SKEW: i64 = 24 * 3600;
+
+/// Payloads checked beyond their types
+pub trait Validate: DeserializeOwned {
+    fn validate(&self) -> Result<(), ValidationError>;
+
+    /// Deserialize the payload. Overridden by payloads flattening a struct, serde loses the path of errors in it.
+    fn from_value(value: serde_json::Value) -> Result<Self, ValidationError> {
+        deserialize(value)
+    }
+}
+
+/// `Json` extractor which also validates the payload, both kinds of errors are answered as `ValidationError`
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        // syntax and content type errors are left to the Json extractor
+        let Json(value) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let payload = T::from_value(value).map_err(IntoResponse::into_response)?;
+        payload.validate().map_err(IntoResponse::into_response)?;
+
+        Ok(ValidJson(payload))
+    }
+}
+
+/// Parse and validate one JSON document, e.g. a line of a stream
+pub fn parse<T: Validate>(json: &str) -> Result<T, ValidationError> {
+    let value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| ValidationError { field: None, message: e.to_string() })?;
+    let payload = T::from_value(value)?;
+    payload.validate()?;
+    Ok(payload)
+}
+
+fn deserialize<T: DeserializeOwned>(value: serde_json::Value) -> Result<T, ValidationError> {
+    serde_path_to_error::deserialize(value)
+        .map_err(|e| deserialize_error(e.path().to_string(), e.inner().to_string()))
+}
+
+/// Field of a deserialization error: its path, or the missing field named by serde
+fn deserialize_error(path: String, message: String) -> ValidationError {
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+        .map(str::to_string);
+
+    let field = match (path.as_str(), missing) {
+        (".", Some(missing)) => Some(missing),
+        (_, Some(missing)) => Some(format!("{}.{}", path, missing)),
+        (".", None) => None,
+        (_, None) => Some(path),
+    };
+
+    ValidationError { field, message }
+}
+
+impl Validate for CreateExecution {
+    fn validate(&self) -> Result<(), ValidationError> {
+        required("name", &self.name, MAX_NAME_LEN)?;
+        optional("tag", self.tag.as_deref(), MAX_TAG_LEN)?;
+        optional("created_by", self.created_by.as_deref(), MAX_CREATED_BY_LEN)?;
+        if let Some(time_created) = self.time_created {
+            timestamp("time_created", time_created)?;
+        }
+        if self.labels.as_ref().is_some_and(|labels| labels.keys().any(|key| key.trim().is_empty())) {
+            return Err(ValidationError::new("labels", "must not have blank keys"));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for StreamHeader {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.execution.validate().map_err(|e| e.within("execution"))
+    }
+}
+
+impl Validate for CreateTestResultBase {
+    fn validate(&self) -> Result<(), ValidationError> {
+        required("name", &self.name, MAX_NAME_LEN)?;
+        required("platform", &self.platform, MAX_PLATFORM_LEN)?;
+        optional("description", self.description.as_deref(), MAX_DESCRIPTION_LEN)?;
+        if self.execution_time.is_some_and(|time| time < 0) {
+            return Err(ValidationError::new("execution_time", "must not be negative"));
+        }
+        optional("created_by", self.created_by.as_deref(), MAX_CREATED_BY_LEN)?;
+        optional("suite_path", self.suite_path.as_deref(), MAX_SUITE_PATH_LEN)?;
+        if let Some(time_created) = self.time_created {
+            timestamp("time_created", time_created)?;
+        }
+        if let Some(client_id) = &self.client_id {
+            required("client_id", client_id, MAX_KEY_LEN)?;
+        }
+        for (index, step) in self.steps.iter().flatten().enumerate() {
+            step.validate().map_err(|e| e.within(&format!("steps[{}]", index)))?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for TestStep {
+    fn validate(&self) -> Result<(), ValidationError> {
+        required("name", &self.name, MAX_STEP_NAME_LEN)?;
+        if self.duration.is_some_and(|duration| duration < 0) {
+            return Err(ValidationError::new("duration", "must not be negative"));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for CreateTestResult {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.base.validate()
+    }
+
+    fn from_value(value: serde_json::Value) -> Result<Self, ValidationError> {
+        #[derive(Deserialize)]
+        struct Target {
+            execution_id: i64,
+        }
+
+        let Target { execution_id } = deserialize(value.clone())?;
+        let base = deserialize(value)?;
+        Ok(CreateTestResult { execution_id, base })
+    }
+}
+
+impl Validate for UpdateTestCase {
+    fn validate(&self) -> Result<(), ValidationError> {
+        optional("description", self.description.as_ref().and_then(Option::as_deref), MAX_DESCRIPTION_LEN)?;
+        optional("owner", self.owner.as_ref().and_then(Option::as_deref), MAX_OWNER_LEN)?;
+        optional("component", self.component.as_ref().and_then(Option::as_deref), MAX_COMPONENT_LEN)?;
+        optional("suite_path", self.suite_path.as_ref().and_then(Option::as_deref), MAX_SUITE_PATH_LEN)
+    }
+}
+
+impl Validate for CreateOwnershipRule {
+    fn validate(&self) -> Result<(), ValidationError> {
+        required("team", &self.team, MAX_TEAM_LEN)?;
+        validate_rule(self)
+    }
+}
+
+impl Validate for CreateWebhook {
+    fn validate(&self) -> Result<(), ValidationError> {
+        required("url", &self.url, MAX_URL_LEN)?;
+        validate_webhook(self)
+    }
+}
+
+impl Validate for UpdateStatusRequest {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Status::value_of(&self.status)
+            .map(|_| ())
+            .map_err(|e| ValidationError::new("status", e))
+    }
+}
+
+fn required(field: &str, value: &str, max_len: usize) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "must not be blank"));
+    }
+    optional(field, Some(value), max_len)
+}
+
+fn optional(field: &str, value: Option<&str>, max_len: usize) -> Result<(), ValidationError> {
+    match value {
+        Some(value) if value.chars().count() > max_len => {
+            Err(ValidationError::new(field, format!("must have at most {} characters", max_len)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Unix time in seconds, after the epoch and not in the future. Other input formats are converted by the deserializer.
+fn timestamp(field: &str, value: i64) -> Result<(), ValidationError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(i64::MAX);
+
+    if value <= 0 || value > now.saturating_add(MAX_CLOCK_SKEW) {
+        return Err(ValidationError::new(field, "must be a unix time in seconds, not in the future"));
+    }
+    Ok(())
+}
@@ -0,0 +1,250 @@
+// src/webhook.rs
+// Webhooks: execution events queued as deliveries, then signed and sent by the background task
+
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::WebhookConfig;
+use crate::database::{claim_due_deliveries, insert_webhook_delivery, mark_attempt_failed, mark_delivered};
+use crate::error::ValidationError;
+use crate::models::{CreateWebhook, Execution, FailedTest, Summary, Webhook, WebhookDelivery};
+
+pub const EVENT_EXECUTION_FINISHED: &str = "execution.finished";
+pub const EVENT_NEW_FAILURE: &str = "test.new_failure";
+pub const EVENT_THRESHOLD_BREACHED: &str = "execution.threshold_breached";
+pub const EVENT_TYPES: &[&str] = &[EVENT_EXECUTION_FINISHED, EVENT_NEW_FAILURE, EVENT_THRESHOLD_BREACHED];
+
+pub const HEADER_EVENT: &str = "X-Cleopatra-Event";
+pub const HEADER_DELIVERY: &str = "X-Cleopatra-Delivery";
+pub const HEADER_TIMESTAMP: &str = "X-Cleopatra-Timestamp";
+pub const HEADER_SIGNATURE: &str = "X-Cleopatra-Signature";
+
+// Deliveries claimed by one run of the task, and how many of them are sent at once
+const DELIVERY_BATCH_SIZE: i64 = 50;
+const DELIVERY_CONCURRENCY: usize = 8;
+
+// Error messages kept on a delivery are cut to this length
+const MAX_ERROR_LEN: usize = 512;
+
+/// Check a webhook before it is stored
+pub fn validate_webhook(webhook: &CreateWebhook) -> Result<(), ValidationError> {
+    let url = webhook.url.trim();
+    let host = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"));
+    if host.is_none_or(str::is_empty) {
+        return Err(ValidationError::new("url", "must be an http or https URL"));
+    }
+    if webhook.secret.trim().is_empty() {
+        return Err(ValidationError::new("secret", "must not be blank"));
+    }
+    if webhook.events.is_empty() {
+        return Err(ValidationError::new("events", "must not be empty"));
+    }
+    if let Some(event) = webhook.events.iter().find(|event| !EVENT_TYPES.contains(&event.as_str())) {
+        return Err(ValidationError::new(
+            "events",
+            format!("unknown event '{}', must be one of {}", event, EVENT_TYPES.join(", ")),
+        ));
+    }
+    match webhook.min_pass_rate {
+        Some(rate) if !(0.0..=1.0).contains(&rate) => {
+            Err(ValidationError::new("min_pass_rate", "must be between 0 and 1"))
+        }
+        None if webhook.events.iter().any(|event| event == EVENT_THRESHOLD_BREACHED) => Err(ValidationError::new(
+            "min_pass_rate",
+            format!("is required by {}", EVENT_THRESHOLD_BREACHED),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// HMAC-SHA256 of `{timestamp}.{body}` keyed with the secret of the webhook, hex encoded.
+/// The timestamp is signed too so that a captured request can't be replayed later.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Whether a webhook may be sent to an address: loopback, private, link-local and other
+/// non-routable addresses are refused, so that webhooks can't reach the internal network
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => !(ip.is_loopback() || ip.is_unspecified() || ip.is_unique_local() || ip.is_unicast_link_local()),
+        },
+    }
+}
+
+fn is_allowed_host(allowed_hosts: &[String], host: &str) -> bool {
+    allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host))
+}
+
+/// DNS resolver of the webhook client, it drops the addresses which aren't public
+/// unless the host is in `allowed_hosts`. Resolving at connect time leaves no gap
+/// between the check and the connection for the name to resolve elsewhere.
+pub struct PublicResolver {
+    allowed_hosts: Arc<Vec<String>>,
+}
+
+impl PublicResolver {
+    pub fn new(config: &WebhookConfig) -> Self {
+        PublicResolver { allowed_hosts: Arc::new(config.allowed_hosts.clone()) }
+    }
+}
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        let allowed = is_allowed_host(&self.allowed_hosts, &host);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allowed || is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Why a webhook url can't be sent to, IP addresses aren't resolved so they are checked here
+fn refused_target(url: &str, allowed_hosts: &[String]) -> Option<String> {
+    let url = match reqwest::Url::parse(url) {
+        Ok(url) => url,
+        Err(e) => return Some(format!("Invalid url: {}", e)),
+    };
+    let host = url.host_str()?;
+    let ip: IpAddr = host.trim_start_matches('[').trim_end_matches(']').parse().ok()?;
+    (!is_public(ip) && !is_allowed_host(allowed_hosts, host))
+        .then(|| format!("{} isn't a public address", host))
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+/// Queue the events of a finished execution for each webhook subscribed to them.
+/// Threshold breaches are only raised for executions with passed, failed or errored tests.
+pub async fn emit_execution_events(
+    conn: &mut SqliteConnection,
+    execution: &Execution,
+    summary: &Summary,
+    new_failures: &[FailedTest],
+) -> sqlx::Result<()> {
+    let webhooks = sqlx::query_as::<_, Webhook>("SELECT * FROM webhook ORDER BY id")
+        .fetch_all(&mut *conn)
+        .await?;
+    let time_created = now();
+    let evaluated = summary.pass + summary.fail + summary.error > 0;
+
+    for webhook in webhooks {
+        for event in webhook.events.iter() {
+            let data = match event.as_str() {
+                EVENT_EXECUTION_FINISHED => json!({ "execution": execution, "summary": summary }),
+                EVENT_NEW_FAILURE if !new_failures.is_empty() => {
+                    json!({ "execution": execution, "tests": new_failures })
+                }
+                EVENT_THRESHOLD_BREACHED => match webhook.min_pass_rate {
+                    Some(min_pass_rate) if evaluated && summary.pass_rate < min_pass_rate => json!({
+                        "execution": execution,
+                        "summary": summary,
+                        "min_pass_rate": min_pass_rate,
+                    }),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let payload = json!({ "event": event, "time_created": time_created, "data": data });
+            insert_webhook_delivery(&mut *conn, webhook.id, event, &payload.to_string()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Send the due deliveries, a failed attempt is retried with backoff until `max_attempts`.
+/// Returns the number of deliveries attempted.
+pub async fn deliver_due(pool: &SqlitePool, client: &reqwest::Client, config: &WebhookConfig) -> anyhow::Result<usize> {
+    // Long enough for the whole batch to time out, so that the next run doesn't send the same deliveries
+    let lease_in_sec = config.timeout_in_sec as i64 * (DELIVERY_BATCH_SIZE / DELIVERY_CONCURRENCY as i64 + 1);
+    let deliveries = claim_due_deliveries(pool, lease_in_sec, DELIVERY_BATCH_SIZE).await?;
+    let attempted = deliveries.len();
+
+    futures::stream::iter(deliveries)
+        .for_each_concurrent(DELIVERY_CONCURRENCY, |delivery| async move {
+            if let Err(e) = deliver(pool, client, config, &delivery).await {
+                eprintln!("Webhook delivery {} failed: {:?}", delivery.id, e);
+            }
+        })
+        .await;
+
+    Ok(attempted)
+}
+
+async fn deliver(
+    pool: &SqlitePool,
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+    delivery: &WebhookDelivery,
+) -> sqlx::Result<()> {
+    let webhook = sqlx::query_as::<_, Webhook>("SELECT * FROM webhook WHERE id = ?")
+        .bind(delivery.webhook_id)
+        .fetch_optional(pool)
+        .await?;
+    // the webhook was deleted along with its pending deliveries after they were claimed
+    let Some(webhook) = webhook else {
+        return Ok(());
+    };
+
+    if let Some(error) = refused_target(&webhook.url, &config.allowed_hosts) {
+        return mark_attempt_failed(pool, delivery.id, None, &error, config.backoff_in_sec, config.max_attempts).await;
+    }
+
+    let timestamp = now();
+    let response = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(HEADER_EVENT, &delivery.event)
+        .header(HEADER_DELIVERY, delivery.id.to_string())
+        .header(HEADER_TIMESTAMP, timestamp.to_string())
+        .header(HEADER_SIGNATURE, format!("sha256={}", sign(&webhook.secret, timestamp, &delivery.payload)))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    let (http_status, error) = match response {
+        Ok(response) if response.status().is_success() => {
+            return mark_delivered(pool, delivery.id, response.status().as_u16() as i64).await;
+        }
+        Ok(response) => (Some(response.status().as_u16() as i64), format!("HTTP {}", response.status())),
+        Err(e) => (None, e.to_string()),
+    };
+    let error: String = error.chars().take(MAX_ERROR_LEN).collect();
+    mark_attempt_failed(pool, delivery.id, http_status, &error, config.backoff_in_sec, config.max_attempts).await
+}
@@ -0,0 +1,87 @@
+// Integration tests for the artifact API
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::TestArtifact;
+
+#[tokio::test]
+async fn test_artifacts() {
+    let execution = common::helper::create_execution(r#"{"name":"Execution with Artifacts","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let create_result_json = format!(r#"{{"execution_id":{},"name":"test_with_screenshot","platform":"web","status":"F"}}"#, execution_id);
+    common::helper::create_result(&create_result_json)
+        .await
+        .expect("Failed to create result");
+
+    common::helper::wait();
+
+    let result_id = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .and_then(|results| results.first().and_then(|result| result.id))
+        .expect("Expected the result id");
+
+    let screenshot = b"\x89PNG fake screenshot".to_vec();
+    let (status, first) = common::helper::upload_artifact(result_id, "login.png", "screenshot", "image/png", screenshot.clone())
+        .await
+        .expect("Failed to upload artifact");
+    assert_eq!(status, 201);
+    let first: TestArtifact = serde_json::from_value(first).expect("Expected an artifact");
+    assert_eq!(first.test_result_id, result_id);
+    assert_eq!(first.kind, "screenshot");
+    assert_eq!(first.size, screenshot.len() as i64);
+    assert_eq!(first.sha256.len(), 64);
+
+    // the same content is stored once
+    let (_, second) = common::helper::upload_artifact(result_id, "login-again.png", "screenshot", "image/png", screenshot.clone())
+        .await
+        .expect("Failed to upload artifact");
+    let second: TestArtifact = serde_json::from_value(second).expect("Expected an artifact");
+    assert_ne!(second.id, first.id);
+    assert_eq!(second.sha256, first.sha256);
+
+    let (_, log) = common::helper::upload_artifact(result_id, "console.txt", "log", "text/plain", b"console output".to_vec())
+        .await
+        .expect("Failed to upload artifact");
+    let log: TestArtifact = serde_json::from_value(log).expect("Expected an artifact");
+    assert_ne!(log.sha256, first.sha256);
+
+    let artifacts = common::helper::get_artifacts(result_id)
+        .await
+        .expect("Failed to get artifacts");
+    let names: Vec<&str> = artifacts.iter().map(|artifact| artifact.name.as_str()).collect();
+    assert_eq!(names, vec!["login.png", "login-again.png", "console.txt"]);
+
+    let (content_type, content) = common::helper::download_artifact(first.id)
+        .await
+        .expect("Failed to download artifact");
+    assert_eq!(content_type, "image/png");
+    assert_eq!(content, screenshot);
+
+    // invalid uploads
+    let (status, error) = common::helper::upload_artifact(result_id, "video.mp4", "movie", "video/mp4", b"frames".to_vec())
+        .await
+        .expect("Failed to send upload");
+    assert_eq!(status, 400);
+    assert_eq!(error["field"], "kind");
+
+    let (status, error) = common::helper::upload_artifact(-1, "login.png", "screenshot", "image/png", screenshot)
+        .await
+        .expect("Failed to send upload");
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "NOT_FOUND");
+
+    let too_large = vec![0u8; 21 * 1024 * 1024];
+    let (status, error) = common::helper::upload_artifact(result_id, "huge.bin", "file", "application/octet-stream", too_large)
+        .await
+        .expect("Failed to send upload");
+    assert_eq!(status, 413);
+    assert_eq!(error["error"], "PAYLOAD_TOO_LARGE");
+
+    assert!(common::helper::download_artifact(-1).await.is_err());
+}
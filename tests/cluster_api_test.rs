@@ -0,0 +1,100 @@
+// Integration tests for the failure cluster API
+// Assumes the server is already running
+
+mod common;
+
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+
+async fn create_execution(name: &str) -> i64 {
+    let create_execution_body = format!(r#"{{
+        "name": "{}",
+        "tag": "cluster-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }}"#, name);
+
+    let execution = common::helper::create_execution(&create_execution_body)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+
+    execution.id.expect("Execution ID should be a number")
+}
+
+#[tokio::test]
+async fn test_failure_clusters() {
+    // a random service name which only appears in the logs of this test
+    let service = Words(3..4).fake::<Vec<String>>().join("_");
+
+    // and a random time window which only holds the test results of this test
+    let time_created: i64 = (1_300_000_000..1_600_000_000).fake();
+
+    let execution_id = create_execution("Test Execution for Clusters").await;
+
+    // three failures caused by the same outage, with different numbers, ids, timestamps and paths
+    let outage_1 = format!(r#"{{"name":"test_order_1","platform":"web","status":"F","execution_time":1500,"log":"step 1 ok\nERROR 2024-01-02T10:00:01Z connection refused by {} at 10.0.0.12:8080 request 550e8400-e29b-41d4-a716-446655440000 (/tmp/run_1/order.rs:42)","time_created":{}}}"#, service, time_created + 1);
+    let outage_2 = format!(r#"{{"name":"test_order_2","platform":"web","status":"F","execution_time":1600,"log":"step 1 ok\nstep 2 ok\nERROR 2024-03-05T11:22:33Z connection refused by {} at 10.0.0.13:8081 request 123e4567-e89b-12d3-a456-426614174000 (/tmp/run_2/order.rs:57)","time_created":{}}}"#, service, time_created + 2);
+    let outage_3 = format!(r#"{{"name":"test_order_3","platform":"api","status":"F","execution_time":1700,"log":"ERROR 2025-12-31T23:59:59Z connection refused by {} at 192.168.1.1:9090 request 9b2f4c6e-1d3a-4e5f-8a7b-0c1d2e3f4a5b (/var/ci/order.rs:7)","time_created":{}}}"#, service, time_created + 3);
+    // a different failure and a passed test which must not be clustered
+    let assertion = format!(r#"{{"name":"test_total","platform":"web","status":"F","execution_time":800,"log":"assertion failed in {}: expected 42 got 41","time_created":{}}}"#, service, time_created + 4);
+    let passed = format!(r#"{{"name":"test_passed","platform":"web","status":"P","execution_time":800,"log":"ERROR lines in a passed test are ignored","time_created":{}}}"#, time_created + 5);
+
+    common::helper::stream_create_results(execution_id, vec![&outage_1, &outage_2, &outage_3, &assertion, &passed])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let clusters = common::helper::get_clusters(Some(execution_id), &[])
+        .await
+        .expect("Failed to get execution clusters");
+
+    assert_eq!(clusters.execution_id, Some(execution_id));
+    assert_eq!(clusters.total, 2);
+    assert_eq!(clusters.items.len(), 2);
+
+    let outage = &clusters.items[0];
+    assert_eq!(outage.count, 3);
+    assert_eq!(outage.executions, 1);
+    assert_eq!(outage.examples.len(), 3);
+    assert!(outage.message.contains(&format!("connection refused by {}", service)));
+    assert!(outage.message.contains("<path>"));
+
+    // the filters of the result listings apply as well
+    let api_clusters = common::helper::get_clusters(Some(execution_id), &[("platform", "api"), ("name_prefix", "test_order")])
+        .await
+        .expect("Failed to get filtered clusters");
+    assert_eq!(api_clusters.total, 1);
+    assert_eq!(api_clusters.items[0].count, 1);
+    assert_eq!(api_clusters.items[0].examples[0].name, "test_order_3");
+
+    let assertion_cluster = &clusters.items[1];
+    assert_eq!(assertion_cluster.count, 1);
+    assert_eq!(assertion_cluster.examples[0].name, "test_total");
+
+    // the same outage in another execution joins the cluster across executions
+    let another_execution_id = create_execution("Another Execution for Clusters").await;
+    common::helper::stream_create_results(another_execution_id, vec![&outage_1])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let from = time_created.to_string();
+    let to = (time_created + 5).to_string();
+    let all_clusters = common::helper::get_clusters(None, &[("from", &from), ("to", &to), ("examples", "1")])
+        .await
+        .expect("Failed to get clusters");
+    assert_eq!(all_clusters.total, 2);
+
+    let cluster = all_clusters.items.iter()
+        .find(|cluster| cluster.signature == outage.signature)
+        .expect("Expected the outage cluster across executions");
+    assert_eq!(cluster.count, 4);
+    assert_eq!(cluster.executions, 2);
+    assert_eq!(cluster.examples.len(), 1);
+    assert_eq!(cluster.examples[0].execution_id, another_execution_id);
+}
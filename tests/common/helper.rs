@@ -3,7 +3,7 @@ use std::{collections::HashMap, thread};
 use std::time::Duration;
 use reqwest;
 use serde_json::Value;
-use cleopatra::{models::{Execution, TestResult, CreateTestResultResponse, StreamResponse, ExecutionListResponse, SuggestResponse}};
+use cleopatra::{models::{Execution, TestResult, CreateTestResultResponse, StreamResponse, ExecutionListResponse, SuggestResponse, SearchResponse, ClusterListResponse, TestHistoryResponse, DurationStatsResponse, RegressionResponse, ExecutionResultsResponse, ExecutionSummariesResponse, Quarantine, QuarantineListResponse, TestArtifact, TestCase, TestCaseListResponse, OwnershipRuleListResponse, TeamFailuresResponse, Webhook, WebhookDeliveryListResponse}};
 use anyhow::Result;
 
 /// Get test results for a given execution ID by calling the API
@@ -45,6 +45,29 @@ pub async fn get_results(execution_id: i64) -> Result<Option<Vec<TestResult>>> {
     }
 }
 
+/// Get the test results page of an execution by calling the API with query parameters
+/// Returns the whole response, summary included if requested
+#[allow(dead_code)]
+pub async fn get_results_with_params(execution_id: i64, params: &[(&str, &str)]) -> Result<ExecutionResultsResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_execution_result_api_url(execution_id))
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let results_response: ExecutionResultsResponse = response.json().await?;
+        Ok(results_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
 /// Get a specific test result by its ID by calling the API
 /// Returns the test result object, or None if no result is found
 #[allow(dead_code)]
@@ -94,6 +117,31 @@ pub async fn create_execution(execution_json: &str) -> Result<Option<Execution>>
     }
 }
 
+/// Create an execution by calling the API with an Idempotency-Key header
+/// Returns the http status and the execution
+#[allow(dead_code)]
+pub async fn create_execution_with_key(execution_json: &str, idempotency_key: &str) -> Result<(u16, Execution)> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_execution_api_url())
+        .header("Content-Type", "application/json")
+        .header("Idempotency-Key", idempotency_key)
+        .body(execution_json.to_string())
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let execution: Execution = response.json().await?;
+        Ok((status.as_u16(), execution))
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
 /// Get executions by calling the API
 /// Returns a list of executions
 #[allow(dead_code)]
@@ -154,6 +202,30 @@ pub async fn get_executions_with_filters(filters: &HashMap<String, String>) -> R
     }
 }
 
+/// Get the summaries of several executions by calling the API
+#[allow(dead_code)]
+pub async fn get_execution_summaries(execution_ids: &[i64]) -> Result<ExecutionSummariesResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let ids: Vec<String> = execution_ids.iter().map(|id| id.to_string()).collect();
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_executions_summary_api_url())
+        .query(&[("ids", ids.join(","))])
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let summaries: ExecutionSummariesResponse = response.json().await?;
+        Ok(summaries)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
 #[allow(dead_code)]
 pub async fn create_result(request_json: &str) -> Result<Option<CreateTestResultResponse>> {
     let config = crate::common::test_config::get_config()?;
@@ -206,6 +278,95 @@ pub async fn stream_create_results(execution_id: i64, results: Vec<&str>) -> Res
     }
 }
 
+/// Create multiple test results by calling the stream API with an Idempotency-Key header
+#[allow(dead_code)]
+pub async fn stream_create_results_with_key(execution_id: i64, results: Vec<&str>, idempotency_key: &str) -> Result<StreamResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_stream_api_url(execution_id))
+        .header("Content-Type", "application/x-ndjson")
+        .header("Idempotency-Key", idempotency_key)
+        .body(results.join("\n"))
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let stream_response: StreamResponse = response.json().await?;
+        Ok(stream_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Send a request with an Idempotency-Key header
+/// Returns the http status and the body, the response or an error
+#[allow(dead_code)]
+pub async fn post_with_key(url: &str, content_type: &str, body: &str, idempotency_key: &str) -> Result<(u16, Value)> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(url)
+        .header("Content-Type", content_type)
+        .header("Idempotency-Key", idempotency_key)
+        .body(body.to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    Ok((status.as_u16(), body))
+}
+
+/// Create an execution and its test results by calling the stream API with a header line
+/// The first line is the header, the other ones are test results
+#[allow(dead_code)]
+pub async fn stream_create_execution_and_results(lines: Vec<&str>) -> Result<StreamResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_new_execution_stream_api_url())
+        .header("Content-Type", "application/x-ndjson")
+        .body(lines.join("\n"))
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let stream_response: StreamResponse = response.json().await?;
+        Ok(stream_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Create multiple test results by calling the batch API with a JSON array
+#[allow(dead_code)]
+pub async fn batch_create_results(execution_id: i64, results_json: &str) -> Result<StreamResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_batch_api_url(execution_id))
+        .header("Content-Type", "application/json")
+        .body(results_json.to_string())
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let batch_response: StreamResponse = response.json().await?;
+        Ok(batch_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
 /// Update a test result status by calling the API
 #[allow(dead_code)]
 pub async fn update_test_result(result_id: i64, status: String) -> Result<()> {
@@ -232,6 +393,42 @@ pub async fn update_test_result(result_id: i64, status: String) -> Result<()> {
     }
 }
 
+/// Send a request which is expected to be rejected
+/// Returns the http status and the error body
+#[allow(dead_code)]
+pub async fn send_invalid_request(method: reqwest::Method, url: &str, request_json: &str) -> Result<(u16, Value)> {
+    let client = reqwest::Client::new();
+    let response = client
+        .request(method, url)
+        .header("Content-Type", "application/json")
+        .body(request_json.to_string())
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        anyhow::bail!("API request unexpectedly succeeded with status {}", status)
+    }
+    let error: Value = response.json().await?;
+    Ok((status.as_u16(), error))
+}
+
+/// Get any API response as untyped JSON, e.g. with timestamps formatted as strings
+/// Returns the http status and the body
+#[allow(dead_code)]
+pub async fn get_json(url: &str, params: &[(&str, &str)]) -> Result<(u16, Value)> {
+    let client = reqwest::Client::new();
+    let response = client
+        .get(url)
+        .query(params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    Ok((status.as_u16(), body))
+}
+
 /// Get suggested execution names by query by calling the API
 /// Returns a SuggestResponse, or None if no suggestions are found for short queries
 #[allow(dead_code)]
@@ -255,7 +452,618 @@ pub async fn get_executions_suggest(query: &str) -> Result<Option<SuggestRespons
     }
 }
 
+/// Search test results by calling the full-text search API
+/// Takes a list of query parameters, `q` included
+/// Returns a SearchResponse
 #[allow(dead_code)]
-pub fn wait() {
-    thread::sleep(Duration::from_secs(3));
-}
\ No newline at end of file
+pub async fn search_results(params: &[(&str, &str)]) -> Result<SearchResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_results_search_api_url())
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let search_response: SearchResponse = response.json().await?;
+        Ok(search_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get failure clusters by calling the API
+/// Lists the clusters of one execution if an execution ID is given, otherwise across executions
+#[allow(dead_code)]
+pub async fn get_clusters(execution_id: Option<i64>, params: &[(&str, &str)]) -> Result<ClusterListResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let url = match execution_id {
+        Some(execution_id) => config.get_execution_clusters_api_url(execution_id),
+        None => config.get_clusters_api_url(),
+    };
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(url)
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let cluster_response: ClusterListResponse = response.json().await?;
+        Ok(cluster_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get the history of a test across executions by calling the API
+#[allow(dead_code)]
+pub async fn get_test_history(test_name: &str, params: &[(&str, &str)]) -> Result<TestHistoryResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_test_history_api_url(test_name))
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let history_response: TestHistoryResponse = response.json().await?;
+        Ok(history_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get the history of a test case by calling the API
+#[allow(dead_code)]
+pub async fn get_test_case_history(test_case_id: i64, params: &[(&str, &str)]) -> Result<TestHistoryResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_test_case_history_api_url(test_case_id))
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let history_response: TestHistoryResponse = response.json().await?;
+        Ok(history_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get duration statistics of a test by calling the API
+#[allow(dead_code)]
+pub async fn get_test_duration(test_name: &str, params: &[(&str, &str)]) -> Result<DurationStatsResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_test_duration_api_url(test_name))
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let duration_response: DurationStatsResponse = response.json().await?;
+        Ok(duration_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get the duration regressions of an execution by calling the API
+#[allow(dead_code)]
+pub async fn get_execution_regressions(execution_id: i64, params: &[(&str, &str)]) -> Result<RegressionResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_execution_regressions_api_url(execution_id))
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let regression_response: RegressionResponse = response.json().await?;
+        Ok(regression_response)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Export test results by calling the API with query parameters
+/// Returns the content type and the whole body
+#[allow(dead_code)]
+pub async fn export_results(params: &[(&str, &str)]) -> Result<(String, String)> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_results_export_api_url())
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = response.text().await?;
+        Ok((content_type, body))
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Create a quarantine entry by calling the API
+/// Returns the created entry, or None if the request was rejected with a client error
+#[allow(dead_code)]
+pub async fn create_quarantine(quarantine_json: &str) -> Result<Option<Quarantine>> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_quarantine_api_url())
+        .header("Content-Type", "application/json")
+        .body(quarantine_json.to_string())
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let quarantine: Quarantine = response.json().await?;
+        Ok(Some(quarantine))
+    } else if status.is_client_error() {
+        Ok(None)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get a quarantine entry by its ID by calling the API
+/// Returns None if no entry is found
+#[allow(dead_code)]
+pub async fn get_quarantine(quarantine_id: i64) -> Result<Option<Quarantine>> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_quarantine_by_id_api_url(quarantine_id))
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let quarantine: Quarantine = response.json().await?;
+        Ok(Some(quarantine))
+    } else if status == reqwest::StatusCode::NOT_FOUND {
+        Ok(None)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// List quarantine entries by calling the API with query parameters
+#[allow(dead_code)]
+pub async fn get_quarantines(params: &[(&str, &str)]) -> Result<QuarantineListResponse> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_quarantines_api_url())
+        .query(params)
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let quarantines: QuarantineListResponse = response.json().await?;
+        Ok(quarantines)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Replace a quarantine entry by calling the API
+#[allow(dead_code)]
+pub async fn update_quarantine(quarantine_id: i64, quarantine_json: &str) -> Result<Quarantine> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .put(config.get_quarantine_by_id_api_url(quarantine_id))
+        .header("Content-Type", "application/json")
+        .body(quarantine_json.to_string())
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        let quarantine: Quarantine = response.json().await?;
+        Ok(quarantine)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Delete a quarantine entry by calling the API
+/// Returns false if no entry is found
+#[allow(dead_code)]
+pub async fn delete_quarantine(quarantine_id: i64) -> Result<bool> {
+    let config = crate::common::test_config::get_config()?;
+    
+    let client = reqwest::Client::new();
+    let response = client
+        .delete(config.get_quarantine_by_id_api_url(quarantine_id))
+        .send()
+        .await?;
+    
+    let status = response.status();
+    if status.is_success() {
+        Ok(true)
+    } else if status == reqwest::StatusCode::NOT_FOUND {
+        Ok(false)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+#[allow(dead_code)]
+pub fn wait() {
+    thread::sleep(Duration::from_secs(3));
+}
+
+/// Upload an attachment of a test result by calling the API
+/// Returns the http status and the body, the artifact or an error
+#[allow(dead_code)]
+pub async fn upload_artifact(result_id: i64, name: &str, kind: &str, content_type: &str, content: Vec<u8>) -> Result<(u16, Value)> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_result_artifacts_api_url(result_id))
+        .query(&[("name", name), ("kind", kind)])
+        .header("Content-Type", content_type)
+        .body(content)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    Ok((status.as_u16(), body))
+}
+
+/// Get the attachments of a test result by calling the API
+#[allow(dead_code)]
+pub async fn get_artifacts(result_id: i64) -> Result<Vec<TestArtifact>> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_result_artifacts_api_url(result_id))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let artifacts: Vec<TestArtifact> = response.json().await?;
+        Ok(artifacts)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Download the content of an attachment by calling the API
+/// Returns the content type and the content
+#[allow(dead_code)]
+pub async fn download_artifact(artifact_id: i64) -> Result<(String, Vec<u8>)> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_artifact_api_url(artifact_id))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let content_type = response.headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let content = response.bytes().await?.to_vec();
+        Ok((content_type, content))
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get the log of a test result, or a part of it, by calling the API
+/// Returns the http status, the Content-Range header and the body
+#[allow(dead_code)]
+pub async fn get_result_log(result_id: i64, params: &[(&str, &str)]) -> Result<(u16, Option<String>, String)> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_result_log_api_url(result_id))
+        .query(params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let content_range = response.headers()
+        .get("Content-Range")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await?;
+    Ok((status.as_u16(), content_range, body))
+}
+
+/// Get test cases of the catalog by calling the API with query parameters
+#[allow(dead_code)]
+pub async fn get_test_cases(params: &[(&str, &str)]) -> Result<TestCaseListResponse> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_test_cases_api_url())
+        .query(params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let test_cases: TestCaseListResponse = response.json().await?;
+        Ok(test_cases)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Update a test case by calling the API
+#[allow(dead_code)]
+pub async fn update_test_case(test_case_id: i64, update_json: &str) -> Result<TestCase> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .patch(config.get_test_case_api_url(test_case_id))
+        .header("Content-Type", "application/json")
+        .body(update_json.to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let test_case: TestCase = response.json().await?;
+        Ok(test_case)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Create an ownership rule by calling the API
+/// Returns the http status and the body, the rule or an error
+#[allow(dead_code)]
+pub async fn create_ownership_rule(rule_json: &str) -> Result<(u16, Value)> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_ownership_rule_api_url())
+        .header("Content-Type", "application/json")
+        .body(rule_json.to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    Ok((status.as_u16(), body))
+}
+
+/// Get the ownership rules by calling the API
+#[allow(dead_code)]
+pub async fn get_ownership_rules() -> Result<OwnershipRuleListResponse> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_ownership_rules_api_url())
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let rules: OwnershipRuleListResponse = response.json().await?;
+        Ok(rules)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Delete an ownership rule by calling the API
+/// Returns true if deleted, false if not found
+#[allow(dead_code)]
+pub async fn delete_ownership_rule(rule_id: i64) -> Result<bool> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .delete(config.get_ownership_rule_by_id_api_url(rule_id))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(true)
+    } else if status == reqwest::StatusCode::NOT_FOUND {
+        Ok(false)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get the failed tests of an execution grouped by team by calling the API
+#[allow(dead_code)]
+pub async fn get_team_failures(execution_id: i64, params: &[(&str, &str)]) -> Result<TeamFailuresResponse> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_execution_team_failures_api_url(execution_id))
+        .query(params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let failures: TeamFailuresResponse = response.json().await?;
+        Ok(failures)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Finish an execution by calling the API
+/// Returns the http status and the body, the execution or an error
+#[allow(dead_code)]
+pub async fn finish_execution(execution_id: i64) -> Result<(u16, Value)> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_execution_finish_api_url(execution_id))
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    Ok((status.as_u16(), body))
+}
+
+/// Register a webhook by calling the API
+/// Returns the http status and the body, the webhook or an error
+#[allow(dead_code)]
+pub async fn create_webhook(webhook_json: &str) -> Result<(u16, Value)> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(config.get_webhook_api_url())
+        .header("Content-Type", "application/json")
+        .body(webhook_json.to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    Ok((status.as_u16(), body))
+}
+
+/// Get a webhook by ID by calling the API
+/// Returns the webhook, or None if not found
+#[allow(dead_code)]
+pub async fn get_webhook(webhook_id: i64) -> Result<Option<Webhook>> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_webhook_by_id_api_url(webhook_id))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let webhook: Webhook = response.json().await?;
+        Ok(Some(webhook))
+    } else if status == reqwest::StatusCode::NOT_FOUND {
+        Ok(None)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Delete a webhook by calling the API
+/// Returns true if deleted, false if not found
+#[allow(dead_code)]
+pub async fn delete_webhook(webhook_id: i64) -> Result<bool> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .delete(config.get_webhook_by_id_api_url(webhook_id))
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(true)
+    } else if status == reqwest::StatusCode::NOT_FOUND {
+        Ok(false)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
+
+/// Get the deliveries of a webhook by calling the API
+#[allow(dead_code)]
+pub async fn get_webhook_deliveries(webhook_id: i64, params: &[(&str, &str)]) -> Result<WebhookDeliveryListResponse> {
+    let config = crate::common::test_config::get_config()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(config.get_webhook_deliveries_api_url(webhook_id))
+        .query(params)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        let deliveries: WebhookDeliveryListResponse = response.json().await?;
+        Ok(deliveries)
+    } else {
+        let error_text = response.text().await?;
+        anyhow::bail!("API request failed with status {}: {}", status, error_text)
+    }
+}
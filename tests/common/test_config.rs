@@ -44,13 +44,121 @@ impl TestConfig {
         format!("{}/api/executions/{}/result/stream", self.api_base_url, execution_id)
     }
     
+    pub fn get_new_execution_stream_api_url(&self) -> String {
+        format!("{}/api/executions/new/result/stream", self.api_base_url)
+    }
+    
+    pub fn get_batch_api_url(&self, execution_id: i64) -> String {
+        format!("{}/api/executions/{}/results:batch", self.api_base_url, execution_id)
+    }
+    
     pub fn get_test_result_status_api_url(&self, result_id: i64) -> String {
         format!("{}/api/result/{}/status", self.api_base_url, result_id)
     }
     
+    pub fn get_executions_summary_api_url(&self) -> String {
+        format!("{}/api/executions/summary", self.api_base_url)
+    }
+    
     pub fn get_executions_suggest_api_url(&self) -> String {
         format!("{}/api/executions/suggest", self.api_base_url)
     }
+    
+    pub fn get_results_search_api_url(&self) -> String {
+        format!("{}/api/results/search", self.api_base_url)
+    }
+    
+    pub fn get_clusters_api_url(&self) -> String {
+        format!("{}/api/clusters", self.api_base_url)
+    }
+    
+    pub fn get_execution_clusters_api_url(&self, execution_id: i64) -> String {
+        format!("{}/api/execution/{}/clusters", self.api_base_url, execution_id)
+    }
+    
+    pub fn get_test_history_api_url(&self, test_name: &str) -> String {
+        format!("{}/api/tests/{}/history", self.api_base_url, test_name)
+    }
+    
+    pub fn get_test_duration_api_url(&self, test_name: &str) -> String {
+        format!("{}/api/tests/{}/duration", self.api_base_url, test_name)
+    }
+    
+    pub fn get_execution_regressions_api_url(&self, execution_id: i64) -> String {
+        format!("{}/api/execution/{}/regressions", self.api_base_url, execution_id)
+    }
+    
+    pub fn get_results_export_api_url(&self) -> String {
+        format!("{}/api/results/export", self.api_base_url)
+    }
+    
+    pub fn get_quarantine_api_url(&self) -> String {
+        format!("{}/api/quarantine", self.api_base_url)
+    }
+    
+    pub fn get_quarantines_api_url(&self) -> String {
+        format!("{}/api/quarantines", self.api_base_url)
+    }
+    
+    pub fn get_quarantine_by_id_api_url(&self, quarantine_id: i64) -> String {
+        format!("{}/api/quarantine/{}", self.api_base_url, quarantine_id)
+    }
+    
+    pub fn get_test_cases_api_url(&self) -> String {
+        format!("{}/api/test-cases", self.api_base_url)
+    }
+    
+    pub fn get_test_case_api_url(&self, test_case_id: i64) -> String {
+        format!("{}/api/test-case/{}", self.api_base_url, test_case_id)
+    }
+    
+    pub fn get_test_case_history_api_url(&self, test_case_id: i64) -> String {
+        format!("{}/api/test-case/{}/history", self.api_base_url, test_case_id)
+    }
+    
+    pub fn get_ownership_rule_api_url(&self) -> String {
+        format!("{}/api/ownership/rule", self.api_base_url)
+    }
+    
+    pub fn get_ownership_rules_api_url(&self) -> String {
+        format!("{}/api/ownership/rules", self.api_base_url)
+    }
+    
+    pub fn get_ownership_rule_by_id_api_url(&self, rule_id: i64) -> String {
+        format!("{}/api/ownership/rule/{}", self.api_base_url, rule_id)
+    }
+    
+    pub fn get_execution_team_failures_api_url(&self, execution_id: i64) -> String {
+        format!("{}/api/execution/{}/team-failures", self.api_base_url, execution_id)
+    }
+    
+    pub fn get_result_artifacts_api_url(&self, result_id: i64) -> String {
+        format!("{}/api/result/{}/artifacts", self.api_base_url, result_id)
+    }
+    
+    pub fn get_result_log_api_url(&self, result_id: i64) -> String {
+        format!("{}/api/result/{}/log", self.api_base_url, result_id)
+    }
+
+    pub fn get_artifact_api_url(&self, artifact_id: i64) -> String {
+        format!("{}/api/artifact/{}", self.api_base_url, artifact_id)
+    }
+
+    pub fn get_execution_finish_api_url(&self, execution_id: i64) -> String {
+        format!("{}/api/execution/{}/finish", self.api_base_url, execution_id)
+    }
+
+    pub fn get_webhook_api_url(&self) -> String {
+        format!("{}/api/webhook", self.api_base_url)
+    }
+
+    pub fn get_webhook_by_id_api_url(&self, webhook_id: i64) -> String {
+        format!("{}/api/webhook/{}", self.api_base_url, webhook_id)
+    }
+
+    pub fn get_webhook_deliveries_api_url(&self, webhook_id: i64) -> String {
+        format!("{}/api/webhook/{}/deliveries", self.api_base_url, webhook_id)
+    }
 }
 
 pub fn get_config() -> Result<&'static TestConfig, anyhow::Error> {
@@ -0,0 +1,93 @@
+// Integration tests for the duration trend and regression API
+// Assumes the server is already running
+
+mod common;
+
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+
+#[tokio::test]
+async fn test_duration_trend_and_regressions() {
+    // a random test name which is only used by this test
+    let test_name: String = Words(3..4).fake::<Vec<String>>().join("_").chars().take(24).collect();
+
+    // three stable runs, then a run which is much slower
+    let durations = [100, 120, 110, 400];
+    let mut execution_ids = Vec::new();
+    for (i, duration) in durations.iter().enumerate() {
+        let create_execution_body = format!(r#"{{
+            "name": "Test Execution for Duration {}",
+            "tag": "duration-test",
+            "created_by": "test-user",
+            "time_created": {}
+        }}"#, i, 1234567890 + i);
+
+        let execution = common::helper::create_execution(&create_execution_body)
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        let execution_id = execution.id.expect("Execution ID should be a number");
+        execution_ids.push(execution_id);
+
+        let result = format!(r#"{{"name":"{}","platform":"web","status":"P","execution_time":{},"time_created":{}}}"#, test_name, duration, 1234567900 + i);
+        let other = format!(r#"{{"name":"{}_other","platform":"web","status":"P","execution_time":50,"time_created":{}}}"#, test_name, 1234567900 + i);
+
+        common::helper::stream_create_results(execution_id, vec![&result, &other])
+            .await
+            .expect("Failed to send stream request")
+            .expect("Expected stream response");
+    }
+
+    common::helper::wait();
+
+    let duration = common::helper::get_test_duration(&test_name, &[])
+        .await
+        .expect("Failed to get test duration");
+
+    assert_eq!(duration.name, test_name);
+    assert_eq!(duration.items.len(), 1);
+    let stats = &duration.items[0];
+    assert_eq!(stats.platform, "web");
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.min, 100);
+    assert_eq!(stats.max, 400);
+    assert_eq!(stats.p50, 110);
+    assert_eq!(stats.p95, 400);
+
+    // the window only keeps the latest runs
+    let windowed = common::helper::get_test_duration(&test_name, &[("window", "2")])
+        .await
+        .expect("Failed to get windowed test duration");
+    assert_eq!(windowed.window, 2);
+    assert_eq!(windowed.items[0].samples, 2);
+    assert_eq!(windowed.items[0].min, 110);
+
+    // the slow run is flagged against the p50 of the previous runs
+    let regressions = common::helper::get_execution_regressions(execution_ids[3], &[])
+        .await
+        .expect("Failed to get execution regressions");
+
+    assert_eq!(regressions.execution_id, execution_ids[3]);
+    assert_eq!(regressions.total, 1);
+    let regression = &regressions.items[0];
+    assert_eq!(regression.name, test_name);
+    assert_eq!(regression.execution_time, 400);
+    assert_eq!(regression.baseline, 110);
+    assert_eq!(regression.samples, 3);
+
+    let tolerant = common::helper::get_execution_regressions(execution_ids[3], &[("multiple", "5")])
+        .await
+        .expect("Failed to get execution regressions");
+    assert_eq!(tolerant.total, 0);
+
+    // the execution summary carries duration aggregates
+    let results = common::helper::get_results_with_params(execution_ids[3], &[("include_summary", "true")])
+        .await
+        .expect("Failed to get results with summary");
+
+    let summary = results.summary.expect("Expected summary");
+    assert_eq!(summary.total_duration, 450);
+    assert_eq!(summary.slowest.len(), 2);
+    assert_eq!(summary.slowest[0].name, test_name);
+    assert_eq!(summary.slowest[0].execution_time, 400);
+}
@@ -0,0 +1,73 @@
+// Integration tests for the error responses
+// Assumes the server is already running
+
+mod common;
+
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+use reqwest::Method;
+
+#[tokio::test]
+async fn test_error_responses() {
+    let config = common::test_config::get_config().expect("Failed to load config");
+
+    // missing rows
+    let (status, error) = common::helper::send_invalid_request(Method::GET, &config.get_result_by_id_api_url(i64::MAX), "")
+        .await
+        .expect("Expected the result not to be found");
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "NOT_FOUND");
+
+    let (status, error) = common::helper::send_invalid_request(Method::PATCH, &config.get_test_result_status_api_url(i64::MAX), r#"{"status":"P"}"#)
+        .await
+        .expect("Expected the result not to be found");
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "NOT_FOUND");
+
+    // conflicts
+    let test_name: String = Words(3..4).fake::<Vec<String>>().join("_").chars().take(24).collect();
+    let quarantine_json = format!(r#"{{"name":"{}","reason":"flaky","owner":"qa"}}"#, test_name);
+    common::helper::create_quarantine(&quarantine_json)
+        .await
+        .expect("Failed to create quarantine")
+        .expect("Expected quarantine to be created");
+    let (status, error) = common::helper::send_invalid_request(Method::POST, &config.get_quarantine_api_url(), &quarantine_json)
+        .await
+        .expect("Expected the duplicate to be rejected");
+    assert_eq!(status, 409);
+    assert_eq!(error["error"], "CONFLICT");
+
+    // validation errors of nested payloads
+    let (status, error) = common::helper::send_invalid_request(Method::POST, &config.get_new_execution_stream_api_url(), r#"{"execution":{"name":"","time_created":1234567890}}"#)
+        .await
+        .expect("Expected the header line to be rejected");
+    assert_eq!(status, 400);
+    assert_eq!(error["error"], "VALIDATION_ERROR");
+    assert_eq!(error["field"], "execution.name");
+
+    // a limit below 1 isn't read as no limit
+    let listings = [
+        (config.get_executions_api_url(), vec![]),
+        (config.get_clusters_api_url(), vec![]),
+        (config.get_results_search_api_url(), vec![("q", "timeout")]),
+    ];
+    for (url, params) in &listings {
+        for limit in ["-1", "0"] {
+            let mut params = params.clone();
+            params.push(("limit", limit));
+            let (status, error) = common::helper::get_json(url, &params)
+                .await
+                .expect("Expected the limit to be rejected");
+            assert_eq!(status, 400, "status of {}", url);
+            assert_eq!(error["error"], "VALIDATION_ERROR");
+            assert_eq!(error["field"], "limit");
+        }
+    }
+
+    // errors outside the handlers are converted as well
+    let (status, error) = common::helper::send_invalid_request(Method::POST, &config.get_execution_api_url(), "{")
+        .await
+        .expect("Expected the malformed body to be rejected");
+    assert_eq!(status, 400);
+    assert_eq!(error["error"], "BAD_REQUEST");
+}
@@ -48,7 +48,7 @@ async fn test_get_executions() {
         .await
         .expect("Failed to get executions");
 
-    assert!(executions_response.total >= 1);
+    assert!(executions_response.total.expect("Expected total") >= 1);
     assert!(!executions_response.items.is_empty());
 
     let found_execution = executions_response.items.iter().find(|item| {
@@ -90,7 +90,7 @@ async fn test_get_executions_with_filter() {
 
     let created_by_2: String = Name().fake();
     for i in 0..21 {
-        let name: String = Sentence(2..8).fake();
+        let name: String = Sentence(2..8).fake::<String>().chars().take(32).collect();
 
         let create_execution_json = format!(
             r#"{{
@@ -119,7 +119,7 @@ async fn test_get_executions_with_filter() {
         .expect("Failed to get filtered executions");
 
     assert_eq!(filtered_executions.items.len(), 1);
-    assert_eq!(filtered_executions.total, 1);
+    assert_eq!(filtered_executions.total, Some(1));
 
     let filtered_execution = &filtered_executions.items[0];
     assert_eq!(filtered_execution.name, "Test Execution 1");
@@ -133,7 +133,7 @@ async fn test_get_executions_with_filter() {
         .await
         .expect("Failed to get filtered executions");
     assert_eq!(filtered_executions2.items.len(), 10);
-    assert_eq!(filtered_executions2.total, 21);
+    assert_eq!(filtered_executions2.total, Some(21));
 }
 
 #[tokio::test]
@@ -188,3 +188,315 @@ async fn test_get_suggested_executions() {
     
     assert!(empty_suggest_response.suggestions.is_empty());
 }
+
+#[tokio::test]
+async fn test_execution_summary() {
+    let created_by: String = Name().fake();
+    let create_execution_json = format!(r#"{{
+        "name": "Test Execution for Summary",
+        "tag": "summary-test",
+        "created_by": "{}",
+        "time_created": 1234567890
+    }}"#, created_by);
+
+    let execution = common::helper::create_execution(&create_execution_json)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    // test_retry fails first and passes on the second run
+    let test_results: Vec<&str> = vec![
+        r#"{"name":"test_login","platform":"web","status":"P","execution_time":100,"time_created":1234567891}"#,
+        r#"{"name":"test_signup","platform":"web","status":"F","execution_time":300,"time_created":1234567892}"#,
+        r#"{"name":"test_retry","platform":"api","status":"F","execution_time":200,"time_created":1234567893}"#,
+        r#"{"name":"test_retry","platform":"api","status":"P","execution_time":200,"time_created":1234567894}"#,
+        r#"{"name":"test_skipped","platform":"api","status":"I","time_created":1234567895}"#,
+    ];
+
+    common::helper::stream_create_results(execution_id, test_results)
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let results = common::helper::get_results_with_params(execution_id, &[("include_summary", "true")])
+        .await
+        .expect("Failed to get results with summary");
+
+    let summary = results.summary.expect("Expected summary");
+    assert_eq!(summary.total, 4);
+    assert_eq!(summary.pass, 2);
+    assert_eq!(summary.fail, 1);
+    assert_eq!(summary.ignor, 1);
+    assert!((summary.pass_rate - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(summary.total_duration, 600);
+    assert!((summary.avg_duration - 200.0).abs() < 1e-9);
+    assert_eq!(summary.retried, 1);
+    assert_eq!(summary.flaky, 1);
+
+    assert_eq!(summary.platforms.len(), 2);
+    let api = summary.platforms.iter().find(|p| p.platform == "api").expect("Expected api platform");
+    assert_eq!(api.total, 2);
+    assert_eq!(api.pass, 1);
+    assert_eq!(api.ignor, 1);
+    assert!((api.pass_rate - 1.0).abs() < 1e-9);
+
+    // the summary is available on the execution list too
+    let mut filters = HashMap::new();
+    filters.insert("created_by".to_string(), created_by);
+    filters.insert("include_summary".to_string(), "true".to_string());
+
+    let executions = common::helper::get_executions_with_filters(&filters)
+        .await
+        .expect("Failed to get executions with summary");
+
+    assert_eq!(executions.items.len(), 1);
+    let listed_summary = executions.items[0].summary.as_ref().expect("Expected summary in list");
+    assert_eq!(listed_summary.total, 4);
+    assert_eq!(listed_summary.flaky, 1);
+    assert_eq!(listed_summary.slowest[0].name, "test_signup");
+
+    // status patches keep the materialized summary up to date
+    let signup = results.items.iter().find(|item| item.name == "test_signup").expect("Expected test_signup");
+    common::helper::update_test_result(signup.id.expect("Result should have an ID"), "P".to_string())
+        .await
+        .expect("Failed to update test result status");
+
+    let summaries = common::helper::get_execution_summaries(&[execution_id])
+        .await
+        .expect("Failed to get execution summaries");
+
+    assert_eq!(summaries.items.len(), 1);
+    assert_eq!(summaries.items[0].execution_id, execution_id);
+    let patched_summary = &summaries.items[0].summary;
+    assert_eq!(patched_summary.total, 4);
+    assert_eq!(patched_summary.pass, 3);
+    assert_eq!(patched_summary.fail, 0);
+    assert!((patched_summary.pass_rate - 1.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_get_execution_results_with_filters() {
+    let execution = common::helper::create_execution(r#"{
+        "name": "Test Execution for Filters",
+        "tag": "filter-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let test_results: Vec<&str> = vec![
+        r#"{"name":"login_ok","platform":"web","status":"P","execution_time":100,"created_by":"alice","time_created":1234567891}"#,
+        r#"{"name":"login_bad_password","platform":"android","status":"F","execution_time":300,"created_by":"bob","time_created":1234567892}"#,
+        r#"{"name":"signup_login_link","platform":"ios","status":"E","execution_time":500,"created_by":"alice","time_created":1234567893}"#,
+        r#"{"name":"signup_retry","platform":"web","status":"F","execution_time":700,"created_by":"bob","time_created":1234567894}"#,
+        r#"{"name":"signup_retry","platform":"web","status":"P","execution_time":700,"created_by":"bob","time_created":1234567895}"#,
+        r#"{"name":"login%literal","platform":"api","status":"I","created_by":"carol","time_created":1234567896}"#,
+    ];
+
+    common::helper::stream_create_results(execution_id, test_results)
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let names_of = |params: Vec<(&'static str, &'static str)>| async move {
+        let results = common::helper::get_results_with_params(execution_id, &params)
+            .await
+            .expect("Failed to get filtered results");
+        let mut names: Vec<String> = results.items.into_iter().map(|item| item.name).collect();
+        names.sort();
+        assert_eq!(results.total, Some(names.len() as i64));
+        names
+    };
+
+    assert_eq!(names_of(vec![("platform", "android,ios")]).await, vec!["login_bad_password", "signup_login_link"]);
+    assert_eq!(names_of(vec![("status", "F,errored")]).await, vec!["login_bad_password", "signup_login_link"]);
+    assert_eq!(names_of(vec![("name_prefix", "login")]).await, vec!["login%literal", "login_bad_password", "login_ok"]);
+    assert_eq!(names_of(vec![("name_prefix", "login%")]).await, vec!["login%literal"]);
+    assert_eq!(names_of(vec![("name_contains", "login_")]).await, vec!["login_bad_password", "login_ok", "signup_login_link"]);
+    assert_eq!(names_of(vec![("min_execution_time", "300"), ("max_execution_time", "500")]).await, vec!["login_bad_password", "signup_login_link"]);
+    assert_eq!(names_of(vec![("retried", "true")]).await, vec!["signup_retry"]);
+    assert_eq!(names_of(vec![("created_by", "alice,carol"), ("status", "P,I")]).await, vec!["login%literal", "login_ok"]);
+    assert_eq!(names_of(vec![("from", "1234567892"), ("to", "1234567893")]).await, vec!["login_bad_password", "signup_login_link"]);
+
+    let invalid = common::helper::get_results_with_params(execution_id, &[("min_execution_time", "fast")]).await;
+    assert!(invalid.is_err());
+}
+
+#[tokio::test]
+async fn test_cursor_pagination() {
+    let created_by: String = Name().fake();
+    let mut execution_ids = Vec::new();
+    for i in 0..3 {
+        let create_execution_json = format!(r#"{{
+            "name": "Test Execution for Cursor {}",
+            "tag": "cursor-test",
+            "created_by": "{}",
+            "time_created": 1234567890
+        }}"#, i, created_by);
+
+        let execution = common::helper::create_execution(&create_execution_json)
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        execution_ids.push(execution.id.expect("Execution should have an ID"));
+    }
+    let execution_id = execution_ids[0];
+
+    let test_results: Vec<String> = (0..25)
+        .map(|i| format!(r#"{{"name":"cursor_test_{:02}","platform":"web","status":"P","time_created":{}}}"#, i, 1234567891 + i))
+        .collect();
+    common::helper::stream_create_results(execution_id, test_results.iter().map(|s| s.as_str()).collect())
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    // the first page is an offset page, it hands out the cursor of the next one
+    let first = common::helper::get_results_with_params(execution_id, &[("limit", "10")])
+        .await
+        .expect("Failed to get first page");
+    assert_eq!(first.total, Some(25));
+    assert!(first.has_next);
+
+    let mut names: Vec<String> = first.items.into_iter().map(|item| item.name).collect();
+    let mut cursor = first.next_cursor.expect("Expected next cursor");
+    loop {
+        let page = common::helper::get_results_with_params(execution_id, &[("limit", "10"), ("cursor", &cursor)])
+            .await
+            .expect("Failed to get cursor page");
+        assert_eq!(page.total, None);
+        names.extend(page.items.into_iter().map(|item| item.name));
+        match page.next_cursor {
+            Some(next) => {
+                assert!(page.has_next);
+                cursor = next;
+            }
+            None => {
+                assert!(!page.has_next);
+                break;
+            }
+        }
+    }
+
+    let expected: Vec<String> = (0..25).map(|i| format!("cursor_test_{:02}", i)).collect();
+    assert_eq!(names, expected);
+
+    // the total is counted in cursor mode when asked for
+    let counted = common::helper::get_results_with_params(execution_id, &[("limit", "10"), ("cursor", &cursor), ("include_total", "true")])
+        .await
+        .expect("Failed to get counted cursor page");
+    assert_eq!(counted.total, Some(25));
+
+    let invalid = common::helper::get_results_with_params(execution_id, &[("cursor", "not-a-cursor")]).await;
+    assert!(invalid.is_err());
+
+    // executions are paged latest first
+    let mut filters = HashMap::new();
+    filters.insert("created_by".to_string(), created_by);
+    filters.insert("limit".to_string(), "2".to_string());
+    let first = common::helper::get_executions_with_filters(&filters)
+        .await
+        .expect("Failed to get executions");
+    let first_ids: Vec<i64> = first.items.iter().filter_map(|item| item.id).collect();
+    assert_eq!(first_ids, vec![execution_ids[2], execution_ids[1]]);
+
+    filters.insert("cursor".to_string(), first.next_cursor.expect("Expected next cursor"));
+    let second = common::helper::get_executions_with_filters(&filters)
+        .await
+        .expect("Failed to get executions");
+    let second_ids: Vec<i64> = second.items.iter().filter_map(|item| item.id).collect();
+    assert_eq!(second_ids, vec![execution_ids[0]]);
+    assert!(!second.has_next);
+    assert!(second.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn test_sorted_listings() {
+    let created_by: String = Name().fake();
+    let mut execution_ids = Vec::new();
+    for name in ["sort_charlie", "sort_alpha", "sort_bravo"] {
+        let create_execution_json = format!(r#"{{
+            "name": "{}",
+            "tag": "sort-test",
+            "created_by": "{}",
+            "time_created": 1234567890
+        }}"#, name, created_by);
+
+        let execution = common::helper::create_execution(&create_execution_json)
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        execution_ids.push(execution.id.expect("Execution should have an ID"));
+    }
+    let execution_id = execution_ids[0];
+
+    let test_results: Vec<&str> = vec![
+        r#"{"name":"sort_a","platform":"web","status":"P","execution_time":300,"time_created":1234567891}"#,
+        r#"{"name":"sort_b","platform":"web","status":"F","execution_time":100,"time_created":1234567892}"#,
+        r#"{"name":"sort_c","platform":"web","status":"P","time_created":1234567893}"#,
+        r#"{"name":"sort_d","platform":"web","status":"E","execution_time":300,"time_created":1234567894}"#,
+        r#"{"name":"sort_e","platform":"web","status":"P","execution_time":200,"time_created":1234567895}"#,
+    ];
+    common::helper::stream_create_results(execution_id, test_results)
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    // walk the whole listing two items at a time
+    let walk = |sort: &'static str| async move {
+        let mut names = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut params = vec![("limit", "2".to_string()), ("sort", sort.to_string())];
+            if let Some(cursor) = &cursor {
+                params.push(("cursor", cursor.clone()));
+            }
+            let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+            let page = common::helper::get_results_with_params(execution_id, &params)
+                .await
+                .expect("Failed to get sorted results");
+            names.extend(page.items.into_iter().map(|item| item.name));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                return names;
+            }
+        }
+    };
+
+    // ties are broken by id, results without execution_time come last in descending order
+    assert_eq!(walk("execution_time:desc").await, vec!["sort_d", "sort_a", "sort_e", "sort_b", "sort_c"]);
+    assert_eq!(walk("execution_time").await, vec!["sort_c", "sort_b", "sort_e", "sort_a", "sort_d"]);
+    assert_eq!(walk("failures_first").await, vec!["sort_b", "sort_d", "sort_a", "sort_c", "sort_e"]);
+    assert_eq!(walk("name:desc").await, vec!["sort_e", "sort_d", "sort_c", "sort_b", "sort_a"]);
+
+    let invalid_field = common::helper::get_results_with_params(execution_id, &[("sort", "log")]).await;
+    assert!(invalid_field.is_err());
+
+    // a cursor only continues the sort it was issued for
+    let page = common::helper::get_results_with_params(execution_id, &[("limit", "2"), ("sort", "name")])
+        .await
+        .expect("Failed to get sorted results");
+    let cursor = page.next_cursor.expect("Expected next cursor");
+    let mismatched = common::helper::get_results_with_params(execution_id, &[("sort", "counter"), ("cursor", &cursor)]).await;
+    assert!(mismatched.is_err());
+
+    let mut filters = HashMap::new();
+    filters.insert("created_by".to_string(), created_by);
+    filters.insert("sort".to_string(), "name:asc".to_string());
+    let executions = common::helper::get_executions_with_filters(&filters)
+        .await
+        .expect("Failed to get sorted executions");
+    let names: Vec<&str> = executions.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, vec!["sort_alpha", "sort_bravo", "sort_charlie"]);
+}
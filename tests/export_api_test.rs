@@ -0,0 +1,117 @@
+// Integration tests for the export API
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::{CreateTestResult, Status};
+
+#[tokio::test]
+async fn test_export_results() {
+    let execution = common::helper::create_execution(r#"{
+        "name": "Test Execution for Export",
+        "tag": "export-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let test_results: Vec<&str> = vec![
+        r#"{"name":"export_login","platform":"web","status":"P","execution_time":100,"log":"all good","suite_path":"tests/auth/login.spec.ts","time_created":1234567891}"#,
+        r#"{"name":"export_signup","platform":"web","description":"signup, then login","status":"F","execution_time":200,"log":"expected \"ok\"\ngot error","steps":[{"name":"open signup","status":"P","duration":50},{"name":"submit","status":"F","message":"got error"}],"time_created":1234567892}"#,
+        r#"{"name":"export_logout","platform":"api","status":"P","time_created":1234567893}"#,
+    ];
+    common::helper::stream_create_results(execution_id, test_results)
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let execution_param = execution_id.to_string();
+
+    // NDJSON lines follow the ingestion format, logs are left out by default
+    let (content_type, body) = common::helper::export_results(&[("execution_id", &execution_param)])
+        .await
+        .expect("Failed to export results");
+    assert_eq!(content_type, "application/x-ndjson");
+
+    let lines: Vec<CreateTestResult> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Expected an ingestion line"))
+        .collect();
+    let names: Vec<&str> = lines.iter().map(|line| line.name.as_str()).collect();
+    assert_eq!(names, vec!["export_login", "export_signup", "export_logout"]);
+    assert!(lines.iter().all(|line| line.execution_id == execution_id && line.log.is_none()));
+    assert_eq!(lines[1].status, Status::F);
+
+    // steps are exported in the order they ran
+    let steps = lines[1].steps.as_ref().expect("Expected the steps");
+    let step_names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+    assert_eq!(step_names, vec!["open signup", "submit"]);
+    assert_eq!(steps[1].message.as_deref(), Some("got error"));
+    assert!(lines[0].steps.is_none());
+
+    // the suite path comes from the test case catalog
+    assert_eq!(lines[0].suite_path.as_deref(), Some("tests/auth/login.spec.ts"));
+    assert!(lines[1].suite_path.is_none());
+
+    // filters apply and logs are included on request
+    let (_, body) = common::helper::export_results(&[("execution_id", &execution_param), ("status", "F"), ("include_log", "true")])
+        .await
+        .expect("Failed to export filtered results");
+    let lines: Vec<CreateTestResult> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Expected an ingestion line"))
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].log.as_deref(), Some("expected \"ok\"\ngot error"));
+
+    // CSV quotes the fields holding separators, quotes or line breaks
+    let (content_type, body) = common::helper::export_results(&[("execution_id", &execution_param), ("format", "csv"), ("include_log", "true")])
+        .await
+        .expect("Failed to export results as CSV");
+    assert!(content_type.starts_with("text/csv"));
+    assert!(body.starts_with("id,execution_id,name,platform,description,status,execution_time,counter,screenshot_id,created_by,time_created,log\n"));
+    assert!(body.contains(",export_signup,web,\"signup, then login\",F,200,1,,,1234567892,\"expected \"\"ok\"\"\ngot error\"\n"));
+    assert!(body.contains(",export_logout,api,,P,,1,,,1234567893,\n"));
+
+    let invalid = common::helper::export_results(&[("format", "xml")]).await;
+    assert!(invalid.is_err());
+}
+
+#[tokio::test]
+async fn test_export_steps_of_many_results() {
+    let execution = common::helper::create_execution(r#"{"name":"Export of Many Results","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    // more results than the export loads steps for at once
+    let test_results: Vec<String> = (0..600)
+        .map(|i| format!(r#"{{"name":"export_many_{}","platform":"web","status":"P","steps":[{{"name":"step of {}","status":"P"}}]}}"#, i, i))
+        .collect();
+    common::helper::stream_create_results(execution_id, test_results.iter().map(String::as_str).collect())
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let (_, body) = common::helper::export_results(&[("execution_id", &execution_id.to_string())])
+        .await
+        .expect("Failed to export results");
+    let lines: Vec<CreateTestResult> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Expected an ingestion line"))
+        .collect();
+    assert_eq!(lines.len(), 600);
+    for line in &lines {
+        let steps = line.steps.as_ref().expect("Expected the steps");
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].name, format!("step of {}", line.name.trim_start_matches("export_many_")));
+    }
+}
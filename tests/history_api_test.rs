@@ -0,0 +1,93 @@
+// Integration tests for the test history API
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::Status;
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+
+#[tokio::test]
+async fn test_get_test_history() {
+    // a random test name which is only used by this test
+    let test_name: String = Words(3..4).fake::<Vec<String>>().join("_").chars().take(24).collect();
+    let tag: String = format!("history-{}", Words(2..3).fake::<Vec<String>>().join("-"));
+
+    let statuses = ["P", "F", "P"];
+    let mut execution_ids = Vec::new();
+    for (i, status) in statuses.iter().enumerate() {
+        let create_execution_body = format!(r#"{{
+            "name": "Test Execution for History {}",
+            "tag": "{}",
+            "created_by": "test-user",
+            "time_created": {}
+        }}"#, i, tag, 1234567890 + i);
+
+        let execution = common::helper::create_execution(&create_execution_body)
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        let execution_id = execution.id.expect("Execution ID should be a number");
+        execution_ids.push(execution_id);
+
+        let web_result = format!(r#"{{"name":"{}","platform":"web","status":"{}","execution_time":{},"time_created":{}}}"#, test_name, status, 1000 + i, 1234567900 + i);
+        let api_result = format!(r#"{{"name":"{}_api","platform":"api","status":"P","execution_time":500,"time_created":{}}}"#, test_name, 1234567900 + i);
+
+        common::helper::stream_create_results(execution_id, vec![&web_result, &api_result])
+            .await
+            .expect("Failed to send stream request")
+            .expect("Expected stream response");
+    }
+
+    common::helper::wait();
+
+    let history = common::helper::get_test_history(&test_name, &[])
+        .await
+        .expect("Failed to get test history");
+
+    assert_eq!(history.name, test_name);
+    assert_eq!(history.total, 3);
+    assert_eq!(history.items.len(), 3);
+
+    // latest run first
+    let latest = &history.items[0];
+    assert_eq!(latest.execution_id, execution_ids[2]);
+    assert_eq!(latest.execution_name, "Test Execution for History 2");
+    assert_eq!(latest.execution_tag, Some(tag.clone()));
+    assert_eq!(latest.platform, "web");
+    assert_eq!(latest.status, Status::P);
+    assert_eq!(latest.execution_time, Some(1002));
+    assert_eq!(latest.counter, 1);
+    assert_eq!(history.items[1].status, Status::F);
+
+    // paginate
+    let page = common::helper::get_test_history(&test_name, &[("limit", "2"), ("offset", "2")])
+        .await
+        .expect("Failed to get test history page");
+
+    assert_eq!(page.total, 3);
+    assert_eq!(page.items.len(), 1);
+    assert!(!page.has_next);
+    assert_eq!(page.items[0].execution_id, execution_ids[0]);
+
+    // filter by platform and tag
+    let filtered = common::helper::get_test_history(&test_name, &[("platform", "api"), ("tag", &tag)])
+        .await
+        .expect("Failed to get filtered test history");
+
+    assert_eq!(filtered.total, 0);
+
+    let filtered = common::helper::get_test_history(&test_name, &[("platform", "web"), ("tag", &tag)])
+        .await
+        .expect("Failed to get filtered test history");
+
+    assert_eq!(filtered.total, 3);
+
+    // the filters of the result listings apply as well
+    let filtered = common::helper::get_test_history(&test_name, &[("status", "F,E"), ("min_execution_time", "1001")])
+        .await
+        .expect("Failed to get filtered test history");
+
+    assert_eq!(filtered.total, 1);
+    assert_eq!(filtered.items[0].execution_id, execution_ids[1]);
+}
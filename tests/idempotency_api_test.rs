@@ -0,0 +1,203 @@
+// Integration tests for the Idempotency-Key header and the client_id of test results
+// Assumes the server is already running
+
+mod common;
+
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+use fake::faker::name::en::Name;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn test_replayed_requests() {
+    let created_by: String = Name().fake();
+    let idempotency_key = Words(3..4).fake::<Vec<String>>().join("-");
+
+    let create_execution_json = format!(r#"{{"name":"Idempotent Execution","created_by":"{}","time_created":1234567890}}"#, created_by);
+
+    let (status, execution) = common::helper::create_execution_with_key(&create_execution_json, &idempotency_key)
+        .await
+        .expect("Failed to create execution");
+    assert_eq!(status, 201);
+
+    // the replay gets the first response instead of a new execution
+    let (replay_status, replayed) = common::helper::create_execution_with_key(&create_execution_json, &idempotency_key)
+        .await
+        .expect("Failed to replay execution");
+    assert_eq!(replay_status, 201);
+    assert_eq!(replayed.id, execution.id);
+
+    let execution_id = execution.id.expect("Expected execution id");
+    let results = vec![
+        r#"{"name":"test_idempotent_login","platform":"web","status":"P","time_created":1234567891}"#,
+        r#"{"name":"test_idempotent_logout","platform":"web","status":"F","time_created":1234567892}"#,
+    ];
+
+    let first = common::helper::stream_create_results_with_key(execution_id, results.clone(), &idempotency_key)
+        .await
+        .expect("Failed to stream results");
+    let replayed = common::helper::stream_create_results_with_key(execution_id, results, &idempotency_key)
+        .await
+        .expect("Failed to replay stream");
+    assert_eq!(replayed.received, first.received);
+    assert_eq!(replayed.inserted, first.inserted);
+
+    common::helper::wait();
+
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results");
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.counter == 1));
+}
+
+#[tokio::test]
+async fn test_key_reused_with_another_body() {
+    let config = common::test_config::get_config().expect("Failed to load test config");
+    let created_by: String = Name().fake();
+    let idempotency_key = Words(3..4).fake::<Vec<String>>().join("-");
+
+    let first_json = format!(r#"{{"name":"Reused Key Execution","created_by":"{}"}}"#, created_by);
+    let (status, execution) = common::helper::post_with_key(&config.get_execution_api_url(), "application/json", &first_json, &idempotency_key)
+        .await
+        .expect("Failed to create execution");
+    assert_eq!(status, 201);
+
+    let other_json = format!(r#"{{"name":"Another Execution","created_by":"{}"}}"#, created_by);
+    let (status, body) = common::helper::post_with_key(&config.get_execution_api_url(), "application/json", &other_json, &idempotency_key)
+        .await
+        .expect("Failed to send request");
+    assert_eq!(status, 422);
+    assert_eq!(body["error"], "UNPROCESSABLE");
+
+    // streamed bodies are compared too
+    let execution_id = execution["id"].as_i64().expect("Expected execution id");
+    let stream_url = config.get_stream_api_url(execution_id);
+    let first_lines = r#"{"name":"test_reused_key","platform":"web","status":"P"}"#;
+    let (status, _) = common::helper::post_with_key(&stream_url, "application/x-ndjson", first_lines, &idempotency_key)
+        .await
+        .expect("Failed to stream results");
+    assert_eq!(status, 200);
+
+    let (status, _) = common::helper::post_with_key(&stream_url, "application/x-ndjson", first_lines, &idempotency_key)
+        .await
+        .expect("Failed to replay stream");
+    assert_eq!(status, 200);
+
+    let other_lines = r#"{"name":"test_reused_key","platform":"web","status":"F"}"#;
+    let (status, _) = common::helper::post_with_key(&stream_url, "application/x-ndjson", other_lines, &idempotency_key)
+        .await
+        .expect("Failed to replay stream");
+    assert_eq!(status, 422);
+}
+
+#[tokio::test]
+async fn test_interrupted_request_releases_key() {
+    let config = common::test_config::get_config().expect("Failed to load test config");
+    let created_by: String = Name().fake();
+    let idempotency_key = Words(3..4).fake::<Vec<String>>().join("-");
+
+    let create_execution_json = format!(r#"{{"name":"Interrupted Execution","created_by":"{}"}}"#, created_by);
+    let execution_id = common::helper::create_execution(&create_execution_json)
+        .await
+        .expect("Failed to create execution")
+        .and_then(|execution| execution.id)
+        .expect("Expected execution id");
+
+    // the client disconnects in the middle of the upload
+    let line = r#"{"name":"test_interrupted_upload","platform":"web","status":"P"}"#;
+    let host = config.api_base_url.trim_start_matches("http://");
+    let mut connection = TcpStream::connect(host).await.expect("Failed to connect");
+    let request = format!(
+        "POST /api/executions/{}/result/stream HTTP/1.1\r\nHost: {}\r\nContent-Type: application/x-ndjson\r\nIdempotency-Key: {}\r\nContent-Length: {}\r\n\r\n{}\n",
+        execution_id, host, idempotency_key, line.len() * 10, line
+    );
+    connection.write_all(request.as_bytes()).await.expect("Failed to send request");
+    connection.flush().await.expect("Failed to send request");
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    drop(connection);
+
+    common::helper::wait();
+
+    // the retry runs instead of getting 409 until the key expires
+    let retried = common::helper::stream_create_results_with_key(execution_id, vec![line], &idempotency_key)
+        .await
+        .expect("Failed to retry the stream");
+    assert_eq!(retried.received, 1);
+    assert_eq!(retried.duplicates, 1);
+    assert_eq!(retried.failed, 0);
+
+    // the line accepted before the disconnection isn't written twice
+    common::helper::wait();
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].counter, 1);
+}
+
+#[tokio::test]
+async fn test_replayed_client_ids() {
+    let created_by: String = Name().fake();
+
+    let create_execution_json = format!(r#"{{"name":"Client Id Execution","created_by":"{}","time_created":1234567890}}"#, created_by);
+    let execution_id = common::helper::create_execution(&create_execution_json)
+        .await
+        .expect("Failed to create execution")
+        .and_then(|execution| execution.id)
+        .expect("Expected execution id");
+
+    let first = common::helper::stream_create_results(execution_id, vec![
+        r#"{"name":"test_client_login","platform":"web","status":"P","time_created":1234567891,"client_id":"line-1"}"#,
+        r#"{"name":"test_client_logout","platform":"web","status":"P","time_created":1234567892,"client_id":"line-2"}"#,
+    ])
+        .await
+        .expect("Failed to stream results")
+        .expect("Expected stream response");
+    assert_eq!(first.inserted, 2);
+
+    common::helper::wait();
+
+    // the upload is retried after a dropped connection, with one more line
+    let retried = common::helper::stream_create_results(execution_id, vec![
+        r#"{"name":"test_client_login","platform":"web","status":"P","time_created":1234567891,"client_id":"line-1"}"#,
+        r#"{"name":"test_client_logout","platform":"web","status":"P","time_created":1234567892,"client_id":"line-2"}"#,
+        r#"{"name":"test_client_signup","platform":"web","status":"P","time_created":1234567893,"client_id":"line-3"}"#,
+    ])
+        .await
+        .expect("Failed to stream results")
+        .expect("Expected stream response");
+    assert_eq!(retried.received, 3);
+    assert_eq!(retried.inserted, 1);
+    assert_eq!(retried.duplicates, 2);
+    assert_eq!(retried.failed, 0);
+
+    // a single test result with a client_id already written is acknowledged as a duplicate
+    let replayed_json = format!(r#"{{"execution_id":{},"name":"test_client_login","platform":"web","status":"P","time_created":1234567891,"client_id":"line-1"}}"#, execution_id);
+    let replayed = common::helper::create_result(&replayed_json)
+        .await
+        .expect("Failed to create result")
+        .expect("Expected result response");
+    assert_eq!(replayed.status, "duplicate");
+
+    // a real retry without client_id is still counted
+    let result_json = format!(r#"{{"execution_id":{},"name":"test_client_signup","platform":"web","status":"P","time_created":1234567894}}"#, execution_id);
+    common::helper::create_result(&result_json)
+        .await
+        .expect("Failed to create result");
+
+    common::helper::wait();
+
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results");
+    assert_eq!(results.len(), 3);
+    for result in results {
+        let expected = if result.name == "test_client_signup" { 2 } else { 1 };
+        assert_eq!(result.counter, expected, "counter of {}", result.name);
+    }
+}
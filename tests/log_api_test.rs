@@ -0,0 +1,117 @@
+// Integration tests for large and partial test result logs
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::CreateTestResult;
+
+#[tokio::test]
+async fn test_large_logs() {
+    let execution = common::helper::create_execution(r#"{"name":"Execution with Large Logs","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    // above the 64 KB offload threshold
+    let large_log: String = (0..2000).map(|i| format!("line {:04} of a long console output\n", i)).collect();
+    assert!(large_log.len() > 64 * 1024);
+
+    for (name, log) in [("test_large_log", Some(large_log.as_str())), ("test_no_log", None)] {
+        let create_result_json = serde_json::json!({
+            "execution_id": execution_id,
+            "name": name,
+            "platform": "web",
+            "status": "F",
+            "log": log,
+        });
+        common::helper::create_result(&create_result_json.to_string())
+            .await
+            .expect("Failed to create result")
+            .expect("Expected result to be created");
+    }
+
+    common::helper::wait();
+
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results to be found");
+    let large = results.iter().find(|result| result.name == "test_large_log").expect("Expected the large log result");
+    let no_log = results.iter().find(|result| result.name == "test_no_log").expect("Expected the result without log");
+
+    // listings report the size only
+    assert!(large.log.is_none());
+    assert_eq!(large.log_size, Some(large_log.len() as i64));
+    assert_eq!(no_log.log_size, None);
+
+    let large_id = large.id.expect("Result should have an ID");
+    let result = common::helper::get_result(large_id)
+        .await
+        .expect("Failed to get result")
+        .expect("Expected result to be found");
+    assert_eq!(result.log.as_deref(), Some(large_log.as_str()));
+
+    let (status, content_range, body) = common::helper::get_result_log(large_id, &[])
+        .await
+        .expect("Failed to get log");
+    assert_eq!(status, 200);
+    assert!(content_range.is_none());
+    assert_eq!(body, large_log);
+
+    let (status, content_range, body) = common::helper::get_result_log(large_id, &[("tail", "2")])
+        .await
+        .expect("Failed to get log tail");
+    assert_eq!(status, 206);
+    assert_eq!(body, "line 1998 of a long console output\nline 1999 of a long console output\n");
+    assert_eq!(
+        content_range.as_deref(),
+        Some(format!("bytes {}-{}/{}", large_log.len() - body.len(), large_log.len() - 1, large_log.len()).as_str())
+    );
+
+    // the second line's number
+    let offset = large_log.find('\n').expect("Expected a line break") + 1;
+    let (status, content_range, body) = common::helper::get_result_log(large_id, &[("offset", &offset.to_string()), ("limit", "9")])
+        .await
+        .expect("Failed to get log range");
+    assert_eq!(status, 206);
+    assert_eq!(body, "line 0001");
+    assert_eq!(content_range.as_deref(), Some(format!("bytes {}-{}/{}", offset, offset + 8, large_log.len()).as_str()));
+
+    // a range starting at or past the end of the log isn't satisfiable
+    for offset in [large_log.len(), large_log.len() + 10] {
+        let (status, content_range, body) = common::helper::get_result_log(large_id, &[("offset", &offset.to_string())])
+            .await
+            .expect("Failed to send request");
+        assert_eq!(status, 416);
+        assert_eq!(content_range.as_deref(), Some(format!("bytes */{}", large_log.len()).as_str()));
+        assert!(body.contains("RANGE_NOT_SATISFIABLE"));
+    }
+
+    // exports read offloaded logs too
+    let (_, body) = common::helper::export_results(&[("execution_id", &execution_id.to_string()), ("include_log", "true")])
+        .await
+        .expect("Failed to export results");
+    let lines: Vec<CreateTestResult> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Expected an ingestion line"))
+        .collect();
+    let exported = lines.iter().find(|line| line.name == "test_large_log").expect("Expected the large log line");
+    assert_eq!(exported.log.as_deref(), Some(large_log.as_str()));
+
+    // invalid requests
+    let (status, _, _) = common::helper::get_result_log(large_id, &[("tail", "2"), ("offset", "0")])
+        .await
+        .expect("Failed to send request");
+    assert_eq!(status, 400);
+
+    let (status, _, _) = common::helper::get_result_log(large_id, &[("limit", "-1")])
+        .await
+        .expect("Failed to send request");
+    assert_eq!(status, 400);
+
+    let (status, _, _) = common::helper::get_result_log(no_log.id.expect("Result should have an ID"), &[])
+        .await
+        .expect("Failed to send request");
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,120 @@
+// Integration tests for the ownership rule and team failures API
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::OwnershipRule;
+use fake::Fake;
+
+#[tokio::test]
+async fn test_team_failures() {
+    // a random prefix so that the rules only match the tests of this test
+    let prefix = format!("owned_{}", (100000..999999).fake::<u32>());
+
+    let (status, checkout_rule) = common::helper::create_ownership_rule(&format!(
+        r#"{{"team":"checkout-team","kind":"regex","pattern":"^{}_checkout_"}}"#, prefix
+    ))
+        .await
+        .expect("Failed to create rule");
+    assert_eq!(status, 201);
+    let checkout_rule: OwnershipRule = serde_json::from_value(checkout_rule).expect("Expected a rule");
+    assert_eq!(checkout_rule.field, "name");
+
+    let (_, cart_rule) = common::helper::create_ownership_rule(&format!(
+        r#"{{"team":"cart-team","field":"name","kind":"glob","pattern":"{}_cart*"}}"#, prefix
+    ))
+        .await
+        .expect("Failed to create rule");
+    let cart_rule: OwnershipRule = serde_json::from_value(cart_rule).expect("Expected a rule");
+
+    // the rules of the config come first
+    let rules = common::helper::get_ownership_rules().await.expect("Failed to get rules");
+    assert!(rules.items[0].id.is_none());
+    assert_eq!(rules.items[0].team, "payments-team");
+    assert!(rules.items.iter().any(|rule| rule.id == checkout_rule.id));
+
+    let execution = common::helper::create_execution(r#"{"name":"Execution with Owners","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let test_results = [
+        format!(r#"{{"name":"{}_checkout_card","platform":"web","status":"F"}}"#, prefix),
+        format!(r#"{{"name":"{}_checkout_cash","platform":"web","status":"E"}}"#, prefix),
+        format!(r#"{{"name":"{}_checkout_ok","platform":"web","status":"P"}}"#, prefix),
+        format!(r#"{{"name":"{}_cart_add","platform":"web","status":"F"}}"#, prefix),
+        format!(r#"{{"name":"{}_refund","platform":"web","status":"F","suite_path":"tests/payments/refund.rs"}}"#, prefix),
+        format!(r#"{{"name":"{}_profile","platform":"web","status":"F"}}"#, prefix),
+    ];
+    common::helper::stream_create_results(execution_id, test_results.iter().map(String::as_str).collect())
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let failures = common::helper::get_team_failures(execution_id, &[])
+        .await
+        .expect("Failed to get team failures");
+    let teams: Vec<(Option<&str>, i64)> = failures.items.iter().map(|item| (item.team.as_deref(), item.failures)).collect();
+    assert_eq!(teams, vec![(Some("checkout-team"), 2), (Some("cart-team"), 1), (Some("payments-team"), 1), (None, 1)]);
+    assert_eq!(failures.items[0].tests[0].name, format!("{}_checkout_card", prefix));
+
+    let checkout_only = common::helper::get_team_failures(execution_id, &[("team", "checkout-team")])
+        .await
+        .expect("Failed to get team failures");
+    assert_eq!(checkout_only.items.len(), 1);
+
+    // listings carry the team too
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results to be found");
+    let passed = results.iter().find(|result| result.name.ends_with("_checkout_ok")).expect("Expected the passed result");
+    assert_eq!(passed.team.as_deref(), Some("checkout-team"));
+
+    // the owner of the test case wins over the rules
+    let cart = results.iter().find(|result| result.name.ends_with("_cart_add")).expect("Expected the cart result");
+    common::helper::update_test_case(cart.test_case_id.expect("Expected the test case"), r#"{"owner":"web-team"}"#)
+        .await
+        .expect("Failed to update test case");
+    let cart = common::helper::get_result(cart.id.expect("Result should have an ID"))
+        .await
+        .expect("Failed to get result")
+        .expect("Expected result to be found");
+    assert_eq!(cart.team.as_deref(), Some("web-team"));
+
+    // rules apply when results are read, a deleted rule stops matching at once
+    assert!(common::helper::delete_ownership_rule(checkout_rule.id.expect("Rule should have an ID")).await.expect("Failed to delete rule"));
+    assert!(common::helper::delete_ownership_rule(cart_rule.id.expect("Rule should have an ID")).await.expect("Failed to delete rule"));
+    let failures = common::helper::get_team_failures(execution_id, &[])
+        .await
+        .expect("Failed to get team failures");
+    let teams: Vec<(Option<&str>, i64)> = failures.items.iter().map(|item| (item.team.as_deref(), item.failures)).collect();
+    assert_eq!(teams, vec![(Some("payments-team"), 1), (Some("web-team"), 1), (None, 3)]);
+
+    assert!(!common::helper::delete_ownership_rule(checkout_rule.id.expect("Rule should have an ID")).await.expect("Failed to delete rule"));
+    assert!(common::helper::get_team_failures(-1, &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_invalid_ownership_rules() {
+    let long_team = format!(r#"{{"team":"{}","pattern":"a*"}}"#, "t".repeat(65));
+    for (rule_json, field) in [
+        (r#"{"team":"qa","kind":"regex","pattern":"("}"#, "pattern"),
+        (r#"{"team":"qa","kind":"glob","pattern":"a[b"}"#, "pattern"),
+        (r#"{"team":"qa","kind":"wildcard","pattern":"a*"}"#, "kind"),
+        (r#"{"team":"qa","field":"owner","pattern":"a*"}"#, "field"),
+        (r#"{"team":" ","pattern":"a*"}"#, "team"),
+        (long_team.as_str(), "team"),
+        (r#"{"team":"qa"}"#, "pattern"),
+    ] {
+        let (status, error) = common::helper::create_ownership_rule(rule_json)
+            .await
+            .expect("Failed to send rule");
+        assert_eq!(status, 400, "status of {}", rule_json);
+        assert_eq!(error["error"], "VALIDATION_ERROR", "error of {}", rule_json);
+        assert_eq!(error["field"], field, "field of {}", rule_json);
+    }
+}
@@ -0,0 +1,223 @@
+// Integration tests for the quarantine API
+// Assumes the server is already running
+
+mod common;
+
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+use fake::faker::name::en::Name;
+
+#[tokio::test]
+async fn test_quarantine_crud() {
+    let test_name: String = Words(3..4).fake::<Vec<String>>().join("_").chars().take(24).collect();
+    let owner: String = Name().fake();
+
+    let create_quarantine_json = format!(r#"{{
+        "name": "{}",
+        "platform": "web",
+        "reason": "flaky login timeout",
+        "owner": "{}"
+    }}"#, test_name, owner);
+
+    let quarantine = common::helper::create_quarantine(&create_quarantine_json)
+        .await
+        .expect("Failed to create quarantine")
+        .expect("Expected quarantine to be created");
+
+    assert_eq!(quarantine.name, test_name);
+    assert_eq!(quarantine.platform, Some("web".to_string()));
+    assert_eq!(quarantine.owner, owner);
+    assert_eq!(quarantine.expires_at, None);
+
+    // the same test can't be quarantined twice on one platform
+    let duplicate = common::helper::create_quarantine(&create_quarantine_json)
+        .await
+        .expect("Failed to send duplicate quarantine");
+    assert!(duplicate.is_none());
+
+    let fetched = common::helper::get_quarantine(quarantine.id)
+        .await
+        .expect("Failed to get quarantine")
+        .expect("Expected quarantine to be found");
+    assert_eq!(fetched.reason, "flaky login timeout");
+
+    let update_quarantine_json = format!(r#"{{
+        "name": "{}",
+        "platform": "web",
+        "reason": "waiting for the auth service fix",
+        "owner": "{}",
+        "expires_at": 1234567890
+    }}"#, test_name, owner);
+
+    let updated = common::helper::update_quarantine(quarantine.id, &update_quarantine_json)
+        .await
+        .expect("Failed to update quarantine");
+    assert_eq!(updated.reason, "waiting for the auth service fix");
+    assert_eq!(updated.expires_at, Some(1234567890));
+
+    let owner_param = owner.as_str();
+    let expired = common::helper::get_quarantines(&[("owner", owner_param), ("active", "false")])
+        .await
+        .expect("Failed to list quarantines");
+    assert_eq!(expired.total, 1);
+    assert_eq!(expired.items[0].id, quarantine.id);
+
+    let active = common::helper::get_quarantines(&[("owner", owner_param), ("active", "true")])
+        .await
+        .expect("Failed to list quarantines");
+    assert_eq!(active.total, 0);
+
+    assert!(common::helper::delete_quarantine(quarantine.id).await.expect("Failed to delete quarantine"));
+    assert!(!common::helper::delete_quarantine(quarantine.id).await.expect("Failed to delete quarantine"));
+    assert!(common::helper::get_quarantine(quarantine.id).await.expect("Failed to get quarantine").is_none());
+}
+
+#[tokio::test]
+async fn test_quarantined_results_excluded_from_summary() {
+    let test_name: String = Words(3..4).fake::<Vec<String>>().join("_").chars().take(24).collect();
+    let expired_name = format!("{}_expired", test_name);
+
+    let quarantines = [
+        format!(r#"{{"name":"{}","reason":"flaky","owner":"qa"}}"#, test_name),
+        format!(r#"{{"name":"{}","reason":"flaky","owner":"qa","expires_at":1234567890}}"#, expired_name),
+    ];
+    for quarantine_json in &quarantines {
+        common::helper::create_quarantine(quarantine_json)
+            .await
+            .expect("Failed to create quarantine")
+            .expect("Expected quarantine to be created");
+    }
+
+    let execution = common::helper::create_execution(r#"{
+        "name": "Test Execution for Quarantine",
+        "tag": "quarantine-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let quarantined_result = format!(r#"{{"name":"{}","platform":"web","status":"F","execution_time":100,"time_created":1234567891}}"#, test_name);
+    let expired_result = format!(r#"{{"name":"{}","platform":"web","status":"P","execution_time":100,"time_created":1234567892}}"#, expired_name);
+    let other_result = format!(r#"{{"name":"{}_other","platform":"web","status":"P","execution_time":100,"time_created":1234567893}}"#, test_name);
+
+    common::helper::stream_create_results(execution_id, vec![&quarantined_result, &expired_result, &other_result])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let results = common::helper::get_results_with_params(execution_id, &[("include_summary", "true")])
+        .await
+        .expect("Failed to get results with summary");
+
+    // only the entry without expiry tags its test
+    for item in &results.items {
+        assert_eq!(item.quarantined, item.name == test_name, "unexpected tag on {}", item.name);
+    }
+
+    let summary = results.summary.expect("Expected summary");
+    assert_eq!(summary.total, 2);
+    assert_eq!(summary.pass, 2);
+    assert_eq!(summary.fail, 0);
+    assert_eq!(summary.quarantined, 1);
+    assert!((summary.pass_rate - 1.0).abs() < 1e-9);
+    assert_eq!(summary.platforms.len(), 1);
+    assert_eq!(summary.platforms[0].total, 2);
+
+    let results = common::helper::get_results_with_params(
+        execution_id,
+        &[("include_summary", "true"), ("include_quarantined", "true")],
+    )
+        .await
+        .expect("Failed to get results with summary");
+
+    let summary = results.summary.expect("Expected summary");
+    assert_eq!(summary.total, 3);
+    assert_eq!(summary.fail, 1);
+    assert_eq!(summary.quarantined, 1);
+    assert_eq!(summary.platforms.len(), 1);
+    assert_eq!(summary.platforms[0].total, 3);
+    assert_eq!(summary.platforms[0].fail, 1);
+}
+
+#[tokio::test]
+async fn test_quarantine_by_test_case() {
+    let test_name = format!("quarantine_case_{}", (100000..999999).fake::<u32>());
+
+    let mut execution_ids = Vec::new();
+    for run in 0..3 {
+        let execution = common::helper::create_execution(&format!(r#"{{"name":"Quarantine Test Case {}","created_by":"test-user"}}"#, run))
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        execution_ids.push(execution.id.expect("Execution should have an ID"));
+    }
+
+    // the first execution catalogs the test on web
+    let web_result = format!(r#"{{"name":"{}","platform":"web","status":"F","execution_time":100}}"#, test_name);
+    let ios_result = format!(r#"{{"name":"{}","platform":"ios","status":"F","execution_time":100}}"#, test_name);
+    common::helper::stream_create_results(execution_ids[0], vec![&web_result])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let test_cases = common::helper::get_test_cases(&[("name", &test_name)])
+        .await
+        .expect("Failed to get test cases");
+    assert_eq!(test_cases.total, 1);
+    let web = &test_cases.items[0];
+
+    // name and platform come from the test case
+    let quarantine = common::helper::create_quarantine(&format!(r#"{{"test_case_id":{},"reason":"flaky","owner":"qa"}}"#, web.id))
+        .await
+        .expect("Failed to create quarantine")
+        .expect("Expected quarantine to be created");
+    assert_eq!(quarantine.name, test_name);
+    assert_eq!(quarantine.platform.as_deref(), Some("web"));
+    assert_eq!(quarantine.test_case_id, Some(web.id));
+
+    // the same test case given by name and platform is a duplicate
+    let duplicate = common::helper::create_quarantine(&format!(r#"{{"name":"{}","platform":"web","reason":"flaky","owner":"qa"}}"#, test_name))
+        .await
+        .expect("Failed to send duplicate quarantine");
+    assert!(duplicate.is_none());
+
+    let unknown = common::helper::create_quarantine(r#"{"test_case_id":-1,"reason":"flaky","owner":"qa"}"#)
+        .await
+        .expect("Failed to send quarantine");
+    assert!(unknown.is_none());
+
+    let test_case_id = web.id.to_string();
+    let listed = common::helper::get_quarantines(&[("test_case_id", &test_case_id)])
+        .await
+        .expect("Failed to list quarantines");
+    assert_eq!(listed.total, 1);
+    assert_eq!(listed.items[0].id, quarantine.id);
+
+    // only the results of the test case are tagged, not the same name on another platform
+    for (execution_id, result_json) in [(execution_ids[1], &web_result), (execution_ids[2], &ios_result)] {
+        common::helper::stream_create_results(execution_id, vec![result_json])
+            .await
+            .expect("Failed to send stream request")
+            .expect("Expected stream response");
+    }
+
+    common::helper::wait();
+
+    for (execution_id, quarantined) in [(execution_ids[1], true), (execution_ids[2], false)] {
+        let results = common::helper::get_results(execution_id)
+            .await
+            .expect("Failed to get results")
+            .expect("Expected results to be found");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].quarantined, quarantined, "unexpected tag on {}", results[0].platform);
+    }
+
+    assert!(common::helper::delete_quarantine(quarantine.id).await.expect("Failed to delete quarantine"));
+}
@@ -2,7 +2,10 @@
 // Assumes the server is already running
 mod common;
 
+use std::collections::HashMap;
+
 use cleopatra::models::Status;
+use fake::Fake;
 
 #[tokio::test]
 async fn test_create_result() {
@@ -86,7 +89,13 @@ async fn test_get_result_by_id() {
     assert_eq!(result.description.as_ref().unwrap(), "Test get result by ID");
     assert_eq!(result.status, Status::P);
     assert_eq!(result.execution_time, Some(800));
-    assert_eq!(result.log.as_ref().unwrap(), "Test passed");
+    // Listings leave out the log, it is read from the result itself
+    assert!(result.log.is_none());
+    assert_eq!(result.log_size, Some(11));
+    let full = common::helper::get_result(result.id.expect("Result should have an ID")).await
+        .expect("Failed to get result")
+        .expect("Expected result to be found");
+    assert_eq!(full.log.as_deref(), Some("Test passed"));
     assert_eq!(result.screenshot_id, Some(1002));
     assert_eq!(result.created_by.as_ref().unwrap(), "test-user");
 }
@@ -134,7 +143,7 @@ async fn test_upsert_result() {
         "execution_time": 1200,
         "log": "Second run",
         "created_by": "test-user",
-        "time_created": 12345678999
+        "time_created": 1234567999
     }}"#, execution_id);
 
     common::helper::create_result(&update_result_json).await
@@ -156,7 +165,13 @@ async fn test_upsert_result() {
     assert_eq!(result.description.as_ref().unwrap(), "Test passed");
     assert_eq!(result.status, Status::P);
     assert_eq!(result.execution_time, Some(1200));
-    assert_eq!(result.log.as_ref().unwrap(), "Second run");
+    // Listings leave out the log, it is read from the result itself
+    assert!(result.log.is_none());
+    assert_eq!(result.log_size, Some(10));
+    let full = common::helper::get_result(result.id.expect("Result should have an ID")).await
+        .expect("Failed to get result")
+        .expect("Expected result to be found");
+    assert_eq!(full.log.as_deref(), Some("Second run"));
     assert!(result.screenshot_id.is_none());
     assert_eq!(result.created_by.as_ref().unwrap(), "test-user");
 }
@@ -213,4 +228,300 @@ async fn test_update_result_status() {
         .expect("Expected result to be found");
     
     assert_eq!(updated_result.status, Status::F);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_extended_statuses() {
+    let create_execution_json = r#"{
+        "name": "Execution for Extended Statuses",
+        "tag": "status-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#;
+    
+    let execution = common::helper::create_execution(create_execution_json).await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    
+    let execution_id = execution.id.expect("Execution should have an ID");
+    
+    // single letters and JUnit outcomes are both accepted
+    let test_results: Vec<&str> = vec![
+        r#"{"name":"test_errored","platform":"web","status":"E","log":"ERROR setup failed","time_created":1234567891}"#,
+        r#"{"name":"test_skipped","platform":"web","status":"S","time_created":1234567892}"#,
+        r#"{"name":"test_blocked","platform":"web","status":"B","time_created":1234567893}"#,
+        r#"{"name":"test_known_failure","platform":"web","status":"K","time_created":1234567894}"#,
+        r#"{"name":"test_junit_error","platform":"api","status":"error","time_created":1234567895}"#,
+        r#"{"name":"test_junit_skipped","platform":"api","status":"skipped","time_created":1234567896}"#,
+        r#"{"name":"test_passed","platform":"api","status":"passed","time_created":1234567897}"#,
+    ];
+    
+    let stream_response = common::helper::stream_create_results(execution_id, test_results).await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+    assert_eq!(stream_response.failed, 0);
+    
+    common::helper::wait();
+    
+    let results = common::helper::get_results_with_params(execution_id, &[("include_summary", "true"), ("limit", "100")]).await
+        .expect("Failed to get results with summary");
+    
+    let status_of = |name: &str| results.items.iter()
+        .find(|item| item.name == name)
+        .map(|item| item.status.clone())
+        .expect("Expected test result");
+    assert_eq!(status_of("test_errored"), Status::E);
+    assert_eq!(status_of("test_skipped"), Status::S);
+    assert_eq!(status_of("test_blocked"), Status::B);
+    assert_eq!(status_of("test_known_failure"), Status::K);
+    assert_eq!(status_of("test_junit_error"), Status::E);
+    assert_eq!(status_of("test_junit_skipped"), Status::S);
+    assert_eq!(status_of("test_passed"), Status::P);
+    
+    // errored tests carry a failure signature
+    let errored = results.items.iter().find(|item| item.name == "test_errored").expect("Expected test_errored");
+    assert!(errored.failure_signature.is_some());
+    
+    let summary = results.summary.expect("Expected summary");
+    assert_eq!(summary.total, 7);
+    assert_eq!(summary.pass, 1);
+    assert_eq!(summary.error, 2);
+    assert_eq!(summary.skip, 2);
+    assert_eq!(summary.block, 1);
+    assert_eq!(summary.known, 1);
+    assert!((summary.pass_rate - 1.0 / 3.0).abs() < 1e-9);
+    
+    // the status filter accepts names as well
+    let errored_results = common::helper::get_results_with_params(execution_id, &[("status", "errored")]).await
+        .expect("Failed to get errored results");
+    assert_eq!(errored_results.total, Some(2));
+    
+    // and so does the status patch
+    let blocked = results.items.iter().find(|item| item.name == "test_blocked").expect("Expected test_blocked");
+    let blocked_id = blocked.id.expect("Result should have an ID");
+    common::helper::update_test_result(blocked_id, "skipped".to_string()).await
+        .expect("Failed to update test result status");
+    
+    let updated_result = common::helper::get_result(blocked_id).await
+        .expect("Failed to get updated result")
+        .expect("Expected result to be found");
+    assert_eq!(updated_result.status, Status::S);
+}
+
+#[tokio::test]
+async fn test_junit_statuses() {
+    let create_execution_json = r#"{
+        "name": "Execution for JUnit Statuses",
+        "tag": "junit-status-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#;
+    
+    let execution = common::helper::create_execution(create_execution_json).await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    
+    let execution_id = execution.id.expect("Execution should have an ID");
+    
+    // every JUnit outcome maps to its single letter status
+    let outcomes = [
+        ("passed", Status::P),
+        ("failure", Status::F),
+        ("error", Status::E),
+        ("skipped", Status::S),
+    ];
+    for (outcome, _) in &outcomes {
+        let create_result_json = format!(
+            r#"{{"execution_id":{},"name":"test_junit_{}","platform":"web","status":"{}","time_created":1234567891}}"#,
+            execution_id, outcome, outcome
+        );
+        common::helper::create_result(&create_result_json).await
+            .expect("Failed to create test result")
+            .expect("Expected test result to be created");
+    }
+    
+    common::helper::wait();
+    
+    let results = common::helper::get_results(execution_id).await
+        .expect("Failed to get results by execution ID")
+        .expect("Expected results to be found");
+    assert_eq!(results.len(), outcomes.len());
+    for (outcome, status) in &outcomes {
+        let result = results.iter()
+            .find(|result| result.name == format!("test_junit_{}", outcome))
+            .expect("Expected test result");
+        assert_eq!(&result.status, status, "unexpected status for {}", outcome);
+    }
+    
+    // the status filter and the status patch accept JUnit outcomes, case-insensitive
+    let failures = common::helper::get_results_with_params(execution_id, &[("status", "FAILURE")]).await
+        .expect("Failed to get failed results");
+    assert_eq!(failures.total, Some(1));
+    assert_eq!(failures.items[0].name, "test_junit_failure");
+    
+    let passed_id = results.iter()
+        .find(|result| result.name == "test_junit_passed")
+        .and_then(|result| result.id)
+        .expect("Expected test_junit_passed");
+    common::helper::update_test_result(passed_id, "Error".to_string()).await
+        .expect("Failed to update test result status");
+    
+    let updated_result = common::helper::get_result(passed_id).await
+        .expect("Failed to get updated result")
+        .expect("Expected result to be found");
+    assert_eq!(updated_result.status, Status::E);
+}
+
+#[tokio::test]
+async fn test_server_assigned_timestamps() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Clock before the epoch")
+        .as_secs() as i64;
+    // the test and the user are only used by this test, other tests share the database
+    let test_name = format!("test_server_time_{}", (100000..999999).fake::<u32>());
+    let created_by = format!("server-time-{}", (100000..999999).fake::<u32>());
+
+    // neither the execution nor the result has time_created
+    let create_execution_json = format!(r#"{{"name":"Execution at Server Time","created_by":"{}"}}"#, created_by);
+    let execution = common::helper::create_execution(&create_execution_json).await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    assert!((execution.time_created - now).abs() < 60, "execution time_created {} is not the server time", execution.time_created);
+    assert!((execution.time_received - now).abs() < 60, "execution time_received {} is not the server time", execution.time_received);
+
+    let execution_id = execution.id.expect("Execution should have an ID");
+    let create_result_json = format!(r#"{{"execution_id":{},"name":"{}","platform":"web","status":"P"}}"#, execution_id, test_name);
+    common::helper::create_result(&create_result_json).await
+        .expect("Failed to create result")
+        .expect("Expected result response to be created");
+
+    // received a few seconds later, a client time is kept while the receive time is still the server's
+    common::helper::wait();
+    let create_result_json = format!(r#"{{"execution_id":{},"name":"test_client_time","platform":"web","status":"P","time_created":1234567891}}"#, execution_id);
+    common::helper::create_result(&create_result_json).await
+        .expect("Failed to create result")
+        .expect("Expected result response to be created");
+
+    // a later run of the test which claims an older client time
+    let create_execution_json = format!(r#"{{"name":"Later Execution","created_by":"{}","time_created":1234567890}}"#, created_by);
+    let later_execution = common::helper::create_execution(&create_execution_json).await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let later_execution_id = later_execution.id.expect("Execution should have an ID");
+    assert!(later_execution.time_received >= execution.time_received);
+    let create_result_json = format!(r#"{{"execution_id":{},"name":"{}","platform":"web","status":"F","time_created":1234567891}}"#, later_execution_id, test_name);
+    common::helper::create_result(&create_result_json).await
+        .expect("Failed to create result")
+        .expect("Expected result response to be created");
+
+    common::helper::wait();
+
+    let response = common::helper::get_results_with_params(execution_id, &[("sort", "time_created:desc")]).await
+        .expect("Failed to get results");
+    let names: Vec<&str> = response.items.iter().map(|result| result.name.as_str()).collect();
+    assert_eq!(names, vec![test_name.as_str(), "test_client_time"]);
+
+    let server_time = &response.items[0];
+    assert!((server_time.time_created - now).abs() < 60);
+    assert!(response.items.iter().all(|result| (result.time_received - now).abs() < 60));
+    assert_eq!(response.items[1].time_created, 1234567891);
+
+    let by_received = common::helper::get_results_with_params(execution_id, &[("sort", "time_received:desc")]).await
+        .expect("Failed to get results");
+    let names: Vec<&str> = by_received.items.iter().map(|result| result.name.as_str()).collect();
+    assert_eq!(names, vec!["test_client_time", test_name.as_str()]);
+    assert!(by_received.items[0].time_received > by_received.items[1].time_received);
+
+    // the history lists the latest received run first whatever its client time
+    let history = common::helper::get_test_history(&test_name, &[("platform", "web")]).await
+        .expect("Failed to get test history");
+    assert_eq!(history.total, 2);
+    assert_eq!(history.items[0].execution_id, later_execution_id);
+    assert_eq!(history.items[0].time_created, 1234567891);
+    assert_eq!(history.items[1].execution_id, execution_id);
+
+    // the executions as well, the later one first by time_received and last by its client time
+    let filters = HashMap::from([("created_by".to_string(), created_by.clone()), ("sort".to_string(), "time_received:desc".to_string())]);
+    let executions = common::helper::get_executions_with_filters(&filters).await
+        .expect("Failed to get executions");
+    let ids: Vec<Option<i64>> = executions.items.iter().map(|execution| execution.id).collect();
+    assert_eq!(ids, vec![Some(later_execution_id), Some(execution_id)]);
+
+    let filters = HashMap::from([("created_by".to_string(), created_by), ("sort".to_string(), "time_created:desc".to_string())]);
+    let executions = common::helper::get_executions_with_filters(&filters).await
+        .expect("Failed to get executions");
+    let ids: Vec<Option<i64>> = executions.items.iter().map(|execution| execution.id).collect();
+    assert_eq!(ids, vec![Some(execution_id), Some(later_execution_id)]);
+}
+
+#[tokio::test]
+async fn test_result_steps() {
+    let execution = common::helper::create_execution(r#"{"name":"Test Execution for Steps","tag":"result-test","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let create_result_json = format!(r#"{{
+        "execution_id": {},
+        "name": "test_checkout_steps",
+        "platform": "web",
+        "status": "F",
+        "execution_time": 900,
+        "steps": [
+            {{"name": "Given a cart with 2 items", "status": "P", "duration": 120}},
+            {{"name": "When the user pays by card", "status": "P", "duration": 700}},
+            {{"name": "Then the order is confirmed", "status": "F", "duration": 80, "message": "expected CONFIRMED got PENDING"}}
+        ]
+    }}"#, execution_id);
+    common::helper::create_result(&create_result_json)
+        .await
+        .expect("Failed to create test result")
+        .expect("Expected test result to be created");
+
+    common::helper::wait();
+
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results to be found");
+    // listings leave the steps out
+    assert!(results[0].steps.is_none());
+    let result_id = results[0].id.expect("Result should have an ID");
+
+    let result = common::helper::get_result(result_id)
+        .await
+        .expect("Failed to get result")
+        .expect("Expected result to be found");
+    let steps = result.steps.expect("Expected the steps");
+    let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+    assert_eq!(names, vec!["Given a cart with 2 items", "When the user pays by card", "Then the order is confirmed"]);
+    assert_eq!(steps[1].duration, Some(700));
+    assert_eq!(steps[2].status, Status::F);
+    assert_eq!(steps[2].message.as_deref(), Some("expected CONFIRMED got PENDING"));
+    assert!(steps[0].message.is_none());
+
+    // a rerun replaces the steps
+    let rerun_json = format!(r#"{{
+        "execution_id": {},
+        "name": "test_checkout_steps",
+        "platform": "web",
+        "status": "P",
+        "steps": [{{"name": "Given a cart with 2 items", "status": "P"}}]
+    }}"#, execution_id);
+    common::helper::create_result(&rerun_json)
+        .await
+        .expect("Failed to update test result")
+        .expect("Expected test result to be updated");
+
+    common::helper::wait();
+
+    let result = common::helper::get_result(result_id)
+        .await
+        .expect("Failed to get result")
+        .expect("Expected result to be found");
+    let steps = result.steps.expect("Expected the steps");
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0].duration, None);
+}
@@ -0,0 +1,124 @@
+// Integration tests for the search API
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::Status;
+use fake::Fake;
+use fake::faker::lorem::en::Word;
+
+#[tokio::test]
+async fn test_search_results() {
+    let create_execution_body = r#"{
+        "name": "Test Execution for Search",
+        "tag": "search-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#;
+
+    let execution = common::helper::create_execution(create_execution_body)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+
+    let execution_id = execution.id.expect("Execution ID should be a number");
+    let execution_id_param = execution_id.to_string();
+
+    // a random marker which only appears in the logs of this test
+    let marker = format!("{}{}", Word().fake::<String>(), (100000..999999).fake::<u32>());
+
+    let result_1 = format!(r#"{{"name":"test_checkout","platform":"web","description":"Checkout flow","status":"F","execution_time":1500,"log":"Connection refused by {} while placing order","created_by":"test-user","time_created":1234567891}}"#, marker);
+    let result_2 = format!(r#"{{"name":"test_cart","platform":"api","description":"Cart flow","status":"P","execution_time":900,"log":"Cart is served by {}","created_by":"test-user","time_created":1234567892}}"#, marker);
+    let result_3 = r#"{"name":"test_profile","platform":"web","description":"Profile flow","status":"P","execution_time":700,"log":"Profile loaded","created_by":"test-user","time_created":1234567893}"#;
+
+    common::helper::stream_create_results(execution_id, vec![&result_1, &result_2, result_3])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    let search_response = common::helper::search_results(&[("q", &marker), ("execution_id", &execution_id_param)])
+        .await
+        .expect("Failed to search results");
+
+    assert_eq!(search_response.query, marker);
+    assert_eq!(search_response.total, 2);
+    assert_eq!(search_response.items.len(), 2);
+    for hit in &search_response.items {
+        assert_eq!(hit.execution_id, execution_id);
+        let snippet = hit.snippet.as_ref().expect("Expected a snippet");
+        assert!(snippet.contains(&format!("<mark>{}</mark>", marker)));
+    }
+
+    // combine the query with filters
+    let filtered_response = common::helper::search_results(&[("q", &marker), ("status", "F"), ("platform", "web")])
+        .await
+        .expect("Failed to search results with filters");
+
+    assert_eq!(filtered_response.total, 1);
+    assert_eq!(filtered_response.items[0].name, "test_checkout");
+    assert_eq!(filtered_response.items[0].status, Status::F);
+
+    // the filters of the result listings apply as well
+    let filtered_response = common::helper::search_results(&[("q", &marker), ("status", "F,P"), ("max_execution_time", "1000")])
+        .await
+        .expect("Failed to search results with filters");
+
+    assert_eq!(filtered_response.total, 1);
+    assert_eq!(filtered_response.items[0].name, "test_cart");
+
+    // the name column is searchable and highlighted as well
+    let name_response = common::helper::search_results(&[("q", "test_checkout"), ("execution_id", &execution_id_param)])
+        .await
+        .expect("Failed to search results by name");
+
+    assert_eq!(name_response.total, 1);
+    assert!(name_response.items[0].name_highlight.contains("<mark>"));
+}
+
+#[tokio::test]
+async fn test_search_offloaded_log() {
+    let create_execution_body = r#"{
+        "name": "Search of Offloaded Logs",
+        "tag": "search-test",
+        "created_by": "test-user"
+    }"#;
+
+    let execution = common::helper::create_execution(create_execution_body)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+
+    let execution_id = execution.id.expect("Execution ID should be a number");
+    let execution_id_param = execution_id.to_string();
+
+    let marker = |place: &str| format!("{}{}{}", place, Word().fake::<String>(), (100000..999999).fake::<u32>());
+    let (head, middle, tail) = (marker("head"), marker("middle"), marker("tail"));
+
+    // well above the offload threshold of the dev config, only its head and tail are indexed
+    let padding = "lorem ipsum dolor ".repeat(5000);
+    let log = format!("{} {} {} {} {}", head, padding, middle, padding, tail);
+    let result = serde_json::json!({
+        "name": "test_offloaded_search",
+        "platform": "web",
+        "status": "F",
+        "execution_time": 1500,
+        "log": log,
+    })
+    .to_string();
+
+    common::helper::stream_create_results(execution_id, vec![&result])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    common::helper::wait();
+
+    for (word, expected) in [(&head, 1), (&tail, 1), (&middle, 0)] {
+        let response = common::helper::search_results(&[("q", word), ("execution_id", &execution_id_param)])
+            .await
+            .expect("Failed to search results");
+        assert_eq!(response.total, expected, "hits for {}", word);
+    }
+}
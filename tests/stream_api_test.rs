@@ -3,6 +3,12 @@
 
 mod common;
 
+use std::collections::HashMap;
+
+use fake::Fake;
+use fake::faker::lorem::en::Words;
+use fake::faker::name::en::Name;
+
 #[tokio::test]
 async fn test_stream_results() {
     let create_execution_body = r#"{
@@ -42,7 +48,7 @@ async fn test_stream_results() {
 async fn test_stream_results_with_invalid_data() {
     // First, create an execution to associate the results with
     let create_execution_body = r#"{
-        "name": "Test Execution for Stream Invalid Data",
+        "name": "Execution with Invalid Stream",
         "tag": "stream-test",
         "created_by": "test-user",
         "time_created": 1234567890
@@ -79,6 +85,144 @@ async fn test_stream_results_with_invalid_data() {
     // Verify the failed item details
     let failed_items = stream_response.failed_items.unwrap();
     assert_eq!(failed_items.len(), 1);
+    assert_eq!(failed_items[0].index, 2);
     assert!(failed_items[0].error.contains("unknown variant `X`"));
     assert_eq!(<std::option::Option<std::string::String> as Clone>::clone(&failed_items[0].raw_payload).unwrap(),  invalid_result);
 }
+
+#[tokio::test]
+async fn test_batch_results() {
+    let create_execution_body = r#"{
+        "name": "Test Execution for Batch",
+        "tag": "stream-test",
+        "created_by": "test-user",
+        "time_created": 1234567890
+    }"#;
+
+    let execution = common::helper::create_execution(create_execution_body)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+
+    let execution_id = execution.id.expect("Execution ID should be a number");
+
+    // the second element has an invalid status, the fourth one isn't a test result at all
+    let batch = r#"[
+        {"name":"test_batch_login","platform":"web","status":"P","execution_time":1500,"time_created":1234567891},
+        {"name":"test_batch_invalid","platform":"web","status":"X","time_created":1234567892},
+        {"name":"test_batch_signup","platform":"api","status":"F","log":"Signup failed","time_created":1234567893},
+        42
+    ]"#;
+
+    let batch_response = common::helper::batch_create_results(execution_id, batch)
+        .await
+        .expect("Failed to send batch request");
+
+    assert_eq!(batch_response.status, "P");
+    assert_eq!(batch_response.execution_id, execution_id);
+    assert_eq!(batch_response.received, 4);
+    assert_eq!(batch_response.inserted, 2);
+    assert_eq!(batch_response.failed, 2);
+
+    let failed_items = batch_response.failed_items.expect("Expected failed items");
+    let failed_indexes: Vec<i64> = failed_items.iter().map(|item| item.index).collect();
+    assert_eq!(failed_indexes, vec![1, 3]);
+    assert!(failed_items[0].error.contains("unknown variant `X`"));
+    assert_eq!(failed_items[1].raw_payload.as_deref(), Some("42"));
+
+    common::helper::wait();
+
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results");
+    let mut names: Vec<&str> = results.iter().map(|result| result.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["test_batch_login", "test_batch_signup"]);
+
+    // the body must be an array
+    let not_an_array = common::helper::batch_create_results(execution_id, r#"{"name":"test_batch_login"}"#).await;
+    assert!(not_an_array.is_err());
+
+    let unknown_execution = common::helper::batch_create_results(-1, "[]").await;
+    assert!(unknown_execution.is_err());
+
+    // an unknown action is 404 whatever its body
+    let config = common::test_config::get_config().expect("Failed to load test config");
+    let unknown_action_url = format!("{}/api/executions/{}/results:purge", config.api_base_url, execution_id);
+    let (status, error) = common::helper::send_invalid_request(reqwest::Method::POST, &unknown_action_url, "{")
+        .await
+        .expect("Expected the action to be unknown");
+    assert_eq!(status, 404);
+    assert_eq!(error["error"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_stream_with_header() {
+    let created_by: String = Name().fake();
+    let idempotency_key = Words(3..4).fake::<Vec<String>>().join("-");
+
+    let header = format!(r#"{{"execution":{{"name":"Execution from Stream Header","tag":"stream-test","created_by":"{}","time_created":1234567890,"labels":{{"branch":"main","build":"42"}}}}}}"#, created_by);
+    let lines = [
+        header.as_str(),
+        r#"{"name":"test_header_login","platform":"web","status":"P","time_created":1234567891}"#,
+        r#"{"name":"test_header_signup","platform":"web","status":"F","time_created":1234567892}"#,
+    ];
+
+    // the header line creates the execution in place of `new`
+    let config = common::test_config::get_config().expect("Failed to load test config");
+    let (status, first) = common::helper::post_with_key(&config.get_new_execution_stream_api_url(), "application/x-ndjson", &lines.join("\n"), &idempotency_key)
+        .await
+        .expect("Failed to send stream request");
+    assert_eq!(status, 200);
+    assert_eq!(first["status"], "C");
+    assert_eq!(first["received"], 2);
+    assert_eq!(first["inserted"], 2);
+    let execution_id = first["execution_id"].as_i64().expect("Expected the execution id");
+
+    // a retry with the same Idempotency-Key is answered with the same execution
+    let (status, retried) = common::helper::post_with_key(&config.get_new_execution_stream_api_url(), "application/x-ndjson", &lines.join("\n"), &idempotency_key)
+        .await
+        .expect("Failed to send stream request");
+    assert_eq!(status, 200);
+    assert_eq!(retried["execution_id"], execution_id);
+
+    // later streams go to the execution by its id
+    let second = common::helper::stream_create_results(execution_id, vec![
+        r#"{"name":"test_header_logout","platform":"web","status":"P","time_created":1234567893}"#,
+    ])
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+    assert_eq!(second.inserted, 1);
+
+    common::helper::wait();
+
+    let results = common::helper::get_results(execution_id)
+        .await
+        .expect("Failed to get results")
+        .expect("Expected results");
+    assert_eq!(results.len(), 3);
+
+    let mut filters = HashMap::new();
+    filters.insert("created_by".to_string(), created_by.clone());
+    let executions = common::helper::get_executions_with_filters(&filters)
+        .await
+        .expect("Failed to get executions");
+    assert_eq!(executions.items.len(), 1);
+    assert_eq!(executions.items[0].name, "Execution from Stream Header");
+    let labels = executions.items[0].labels.as_ref().expect("Expected labels");
+    assert_eq!(labels.get("build").map(|s| s.as_str()), Some("42"));
+
+    // a new execution needs the header line, an existing one can't take one
+    let headless = common::helper::stream_create_execution_and_results(vec![
+        r#"{"name":"test_header_login","platform":"web","status":"P","time_created":1234567891}"#,
+    ]).await;
+    assert!(headless.is_err());
+
+    let header_to_existing = common::helper::stream_create_results(execution_id, vec![
+        &header,
+        r#"{"name":"test_header_login","platform":"web","status":"P","time_created":1234567891}"#,
+    ]).await;
+    assert!(header_to_existing.is_err());
+}
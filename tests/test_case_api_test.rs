@@ -0,0 +1,97 @@
+// Integration tests for the test case catalog API
+// Assumes the server is already running
+
+mod common;
+
+use cleopatra::models::TestCase;
+use fake::Fake;
+
+#[tokio::test]
+async fn test_test_case_catalog() {
+    // a random prefix so that the catalog entries only come from this test
+    let prefix = format!("catalog_{}", (100000..999999).fake::<u32>());
+    let name = format!("{}_login", prefix);
+
+    // a test result is unique by name within an execution, each run gets its own
+    let runs = [
+        ("web", r#""description":"login with a valid user","suite_path":"tests/auth/login.rs""#),
+        ("web", r#""description":"changed by the client""#),
+        ("ios", r#""description":"login on iOS""#),
+    ];
+    let mut results = Vec::new();
+    for (run, (platform, fields)) in runs.iter().enumerate() {
+        let execution = common::helper::create_execution(&format!(r#"{{"name":"Catalog Execution {}","created_by":"test-user"}}"#, run))
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        let execution_id = execution.id.expect("Execution should have an ID");
+
+        let create_result_json = format!(r#"{{"execution_id":{},"name":"{}","platform":"{}","status":"P",{}}}"#, execution_id, name, platform, fields);
+        common::helper::create_result(&create_result_json)
+            .await
+            .expect("Failed to create result")
+            .expect("Expected result to be created");
+
+        common::helper::wait();
+
+        let mut execution_results = common::helper::get_results(execution_id)
+            .await
+            .expect("Failed to get results")
+            .expect("Expected results to be found");
+        results.append(&mut execution_results);
+    }
+
+    // one entry per name and platform, whichever execution the results belong to
+    let test_cases = common::helper::get_test_cases(&[("name_prefix", &prefix)])
+        .await
+        .expect("Failed to get test cases");
+    assert_eq!(test_cases.total, 2);
+    let web = test_cases.items.iter().find(|test_case| test_case.platform == "web").expect("Expected the web test case");
+    assert_eq!(web.name, name);
+    assert_eq!(web.description.as_deref(), Some("login with a valid user"));
+    assert_eq!(web.suite_path.as_deref(), Some("tests/auth/login.rs"));
+    assert!(web.owner.is_none());
+
+    for result in results.iter().filter(|result| result.platform == "web") {
+        assert_eq!(result.test_case_id, Some(web.id));
+    }
+    assert_ne!(results[2].test_case_id, Some(web.id));
+
+    let (status, test_case) = common::helper::get_json(&common::test_config::get_config().expect("Failed to load config").get_test_case_api_url(web.id), &[])
+        .await
+        .expect("Failed to get test case");
+    assert_eq!(status, 200);
+    let test_case: TestCase = serde_json::from_value(test_case).expect("Expected a test case");
+    assert_eq!(test_case.name, name);
+
+    // curated fields are kept by later ingestions
+    let updated = common::helper::update_test_case(web.id, r#"{"owner":"auth-team","component":"login"}"#)
+        .await
+        .expect("Failed to update test case");
+    assert_eq!(updated.owner.as_deref(), Some("auth-team"));
+    assert_eq!(updated.description.as_deref(), Some("login with a valid user"));
+
+    let owned = common::helper::get_test_cases(&[("name_prefix", &prefix), ("owner", "auth-team")])
+        .await
+        .expect("Failed to get test cases");
+    assert_eq!(owned.total, 1);
+    assert_eq!(owned.items[0].component.as_deref(), Some("login"));
+
+    // null clears a field, the fields left out are kept
+    let cleared = common::helper::update_test_case(web.id, r#"{"owner":null}"#)
+        .await
+        .expect("Failed to update test case");
+    assert!(cleared.owner.is_none());
+    assert_eq!(cleared.component.as_deref(), Some("login"));
+    assert_eq!(cleared.description.as_deref(), Some("login with a valid user"));
+
+    let history = common::helper::get_test_case_history(web.id, &[])
+        .await
+        .expect("Failed to get test case history");
+    assert_eq!(history.test_case_id, Some(web.id));
+    assert_eq!(history.total, 2);
+    assert!(history.items.iter().all(|item| item.platform == "web"));
+
+    assert!(common::helper::update_test_case(-1, r#"{"owner":"auth-team"}"#).await.is_err());
+    assert!(common::helper::get_test_case_history(-1, &[]).await.is_err());
+}
@@ -0,0 +1,85 @@
+// Integration tests for the input formats of timestamps and the time_format parameter
+// Assumes the server is already running
+
+mod common;
+
+use fake::Fake;
+use fake::faker::name::en::Name;
+
+#[tokio::test]
+async fn test_timestamp_input_formats() {
+    let created_by: String = Name().fake();
+
+    // RFC3339 and milliseconds are stored as unix time in seconds
+    let rfc3339_json = format!(r#"{{"name":"RFC3339 Execution","created_by":"{}","time_created":"2025-01-15T01:13:20+01:00"}}"#, created_by);
+    let execution = common::helper::create_execution(&rfc3339_json)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    assert_eq!(execution.time_created, 1736900000);
+
+    let millis_json = format!(r#"{{"name":"Millisecond Execution","created_by":"{}","time_created":1736900000123}}"#, created_by);
+    let execution = common::helper::create_execution(&millis_json)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    assert_eq!(execution.time_created, 1736900000);
+
+    let execution_id = execution.id.expect("Execution should have an ID");
+    let results = vec![
+        r#"{"name":"test_seconds","platform":"web","status":"P","time_created":1736900010}"#,
+        r#"{"name":"test_millis","platform":"web","status":"P","time_created":1736900020000}"#,
+        r#"{"name":"test_rfc3339","platform":"web","status":"P","time_created":"2025-01-15T00:13:50Z"}"#,
+    ];
+    let stream_response = common::helper::stream_create_results(execution_id, results)
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+    assert_eq!(stream_response.inserted, 3);
+
+    let invalid = format!(r#"{{"execution_id":{},"name":"test_invalid_time","platform":"web","status":"P","time_created":"last tuesday"}}"#, execution_id);
+    let config = common::test_config::get_config().expect("Failed to load config");
+    let (status, error) = common::helper::send_invalid_request(reqwest::Method::POST, &config.get_result_api_url(), &invalid)
+        .await
+        .expect("Expected the timestamp to be rejected");
+    assert_eq!(status, 400);
+    assert_eq!(error["field"], "time_created");
+
+    common::helper::wait();
+
+    // the range filters take every input format as well
+    let response = common::helper::get_results_with_params(execution_id, &[
+        ("sort", "time_created:asc"),
+        ("from", "2025-01-15T00:13:35Z"),
+        ("to", "1736900050000"),
+    ])
+        .await
+        .expect("Failed to get results");
+    let times: Vec<i64> = response.items.iter().map(|result| result.time_created).collect();
+    assert_eq!(times, vec![1736900020, 1736900030]);
+
+    // timestamps of the response as RFC3339
+    let url = config.get_execution_result_api_url(execution_id);
+    let (status, body) = common::helper::get_json(&url, &[("sort", "time_created:asc"), ("time_format", "rfc3339")])
+        .await
+        .expect("Failed to get results");
+    assert_eq!(status, 200);
+    assert_eq!(body["items"][0]["time_created"], "2025-01-15T00:13:30Z");
+    assert!(body["items"][0]["time_received"].is_string());
+    assert_eq!(body["items"][0]["execution_time"], serde_json::Value::Null);
+
+    // the streamed export is passed through in unix time
+    let execution_id_param = execution_id.to_string();
+    let (_, exported) = common::helper::export_results(&[("execution_id", &execution_id_param), ("time_format", "rfc3339")])
+        .await
+        .expect("Failed to export results");
+    let line: serde_json::Value = serde_json::from_str(exported.lines().next().expect("Expected an exported line"))
+        .expect("Expected a JSON line");
+    assert!(line["time_created"].is_i64());
+
+    let (status, error) = common::helper::get_json(&url, &[("time_format", "iso")])
+        .await
+        .expect("Failed to get results");
+    assert_eq!(status, 400);
+    assert_eq!(error["error"], "BAD_REQUEST");
+}
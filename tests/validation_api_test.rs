@@ -0,0 +1,87 @@
+// Integration tests for the validation of request payloads
+// Assumes the server is already running
+
+mod common;
+
+use reqwest::Method;
+
+#[tokio::test]
+async fn test_invalid_execution() {
+    let config = common::test_config::get_config().expect("Failed to load config");
+    let url = config.get_execution_api_url();
+
+    let cases = [
+        (r#"{"name":" ","time_created":1234567890}"#, "name"),
+        (r#"{"name":"a name longer than thirty two characters","time_created":1234567890}"#, "name"),
+        (r#"{"name":"Validation Execution","time_created":-1}"#, "time_created"),
+        (r#"{"name":"Validation Execution","time_created":99999999999}"#, "time_created"),
+        (r#"{"name":"Validation Execution","time_created":"yesterday"}"#, "time_created"),
+    ];
+
+    for (request_json, field) in cases {
+        let (status, error) = common::helper::send_invalid_request(Method::POST, &url, request_json)
+            .await
+            .unwrap_or_else(|e| panic!("Expected {} to be rejected: {}", request_json, e));
+
+        assert_eq!(status, 400, "status of {}", request_json);
+        assert_eq!(error["error"], "VALIDATION_ERROR", "error of {}", request_json);
+        assert_eq!(error["field"], field, "field of {}", request_json);
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_results() {
+    let config = common::test_config::get_config().expect("Failed to load config");
+
+    let execution_id = common::helper::create_execution(r#"{"name":"Validation Execution","time_created":1234567890}"#)
+        .await
+        .expect("Failed to create execution")
+        .and_then(|execution| execution.id)
+        .expect("Expected execution id");
+
+    let negative_duration = format!(r#"{{"execution_id":{},"name":"test_negative","platform":"web","status":"P","execution_time":-5,"time_created":1234567891}}"#, execution_id);
+    let (status, error) = common::helper::send_invalid_request(Method::POST, &config.get_result_api_url(), &negative_duration)
+        .await
+        .expect("Expected the result to be rejected");
+    assert_eq!(status, 400);
+    assert_eq!(error["field"], "execution_time");
+
+    let invalid_status = format!(r#"{{"execution_id":{},"name":"test_invalid_status","platform":"web","status":"X","time_created":1234567891}}"#, execution_id);
+    let (_, error) = common::helper::send_invalid_request(Method::POST, &config.get_result_api_url(), &invalid_status)
+        .await
+        .expect("Expected the result to be rejected");
+    assert_eq!(error["field"], "status");
+
+    let negative_step = format!(r#"{{"execution_id":{},"name":"test_negative_step","platform":"web","status":"P","steps":[{{"name":"open","status":"P"}},{{"name":"close","status":"P","duration":-1}}]}}"#, execution_id);
+    let (status, error) = common::helper::send_invalid_request(Method::POST, &config.get_result_api_url(), &negative_step)
+        .await
+        .expect("Expected the result to be rejected");
+    assert_eq!(status, 400);
+    assert_eq!(error["field"], "steps[1].duration");
+
+    let (status, error) = common::helper::send_invalid_request(Method::PATCH, &config.get_test_result_status_api_url(1), r#"{"status":"X"}"#)
+        .await
+        .expect("Expected the status update to be rejected");
+    assert_eq!(status, 400);
+    assert_eq!(error["field"], "status");
+
+    // invalid lines of a stream are reported with their field, the other lines are still ingested
+    let long_description = "d".repeat(129);
+    let invalid_line = format!(r#"{{"name":"test_long_description","platform":"web","status":"P","description":"{}","time_created":1234567891}}"#, long_description);
+    let response = common::helper::stream_create_results(execution_id, vec![
+        r#"{"name":"test_valid","platform":"web","status":"P","time_created":1234567891}"#,
+        &invalid_line,
+        r#"{"name":"","platform":"web","status":"P","time_created":1234567891}"#,
+    ])
+        .await
+        .expect("Failed to stream results")
+        .expect("Expected stream response");
+
+    assert_eq!(response.inserted, 1);
+    assert_eq!(response.failed, 2);
+    let failed_items = response.failed_items.expect("Expected failed items");
+    assert_eq!(failed_items[0].index, 1);
+    assert_eq!(failed_items[0].field.as_deref(), Some("description"));
+    assert_eq!(failed_items[1].index, 2);
+    assert_eq!(failed_items[1].field.as_deref(), Some("name"));
+}
@@ -0,0 +1,342 @@
+// Integration tests for the webhook API and the delivery of execution events
+// Assumes the server is already running, the webhooks are sent to a stand-in served by the test
+
+mod common;
+
+use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+use cleopatra::models::{Webhook, WebhookDelivery};
+use fake::Fake;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// The delivery task runs every 10 seconds in dev.toml, a retry is sent one run after the first attempt
+const MAX_WAIT_IN_SEC: u32 = 60;
+
+/// A request received by the stand-in
+struct Received {
+    event: String,
+    delivery: String,
+    timestamp: String,
+    signature: String,
+    body: String,
+}
+
+#[derive(Clone, Default)]
+struct StandIn {
+    received: Arc<Mutex<Vec<Received>>>,
+    // the number of requests answered with 500 before the stand-in accepts them
+    failures_left: Arc<Mutex<u32>>,
+}
+
+async fn receive(State(stand_in): State<StandIn>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default().to_string();
+    stand_in.received.lock().unwrap().push(Received {
+        event: header("X-Cleopatra-Event"),
+        delivery: header("X-Cleopatra-Delivery"),
+        timestamp: header("X-Cleopatra-Timestamp"),
+        signature: header("X-Cleopatra-Signature"),
+        body: String::from_utf8_lossy(&body).to_string(),
+    });
+
+    let mut failures_left = stand_in.failures_left.lock().unwrap();
+    if *failures_left > 0 {
+        *failures_left -= 1;
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::OK
+    }
+}
+
+/// Serve the stand-in on a free local port, returns its URL
+async fn start_stand_in(stand_in: StandIn) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind the stand-in");
+    let addr = listener.local_addr().expect("Expected a local address");
+    let app = Router::new().route("/hook", post(receive)).with_state(stand_in);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    format!("http://{}/hook", addr)
+}
+
+fn verify(secret: &str, received: &Received) -> bool {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}.{}", received.timestamp, received.body).as_bytes());
+    received.signature == format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// The requests received by the stand-in about an execution, one per event
+fn accepted_events(stand_in: &StandIn, execution_id: i64) -> Vec<(String, Value)> {
+    let received = stand_in.received.lock().unwrap();
+    let mut events: Vec<(String, Value)> = received
+        .iter()
+        .map(|request| (request.event.clone(), serde_json::from_str::<Value>(&request.body).unwrap()))
+        .filter(|(_, body)| body["data"]["execution"]["id"].as_i64() == Some(execution_id))
+        .collect();
+    events.sort_by(|a, b| a.0.cmp(&b.0));
+    events.dedup_by(|a, b| a.0 == b.0);
+    events
+}
+
+/// Wait until the deliveries of a webhook about the executions are no longer pending, returns them.
+/// Tests run in parallel, a webhook also gets the events of the executions of the other tests.
+async fn wait_for_deliveries(webhook_id: i64, execution_ids: &[i64], expected: usize) -> Vec<WebhookDelivery> {
+    for _ in 0..MAX_WAIT_IN_SEC {
+        let deliveries: Vec<WebhookDelivery> = common::helper::get_webhook_deliveries(webhook_id, &[("limit", "100")])
+            .await
+            .expect("Failed to get deliveries")
+            .items
+            .into_iter()
+            .filter(|delivery| {
+                let payload: Value = serde_json::from_str(&delivery.payload).unwrap();
+                payload["data"]["execution"]["id"].as_i64().is_some_and(|id| execution_ids.contains(&id))
+            })
+            .collect();
+        if deliveries.len() >= expected && deliveries.iter().all(|delivery| delivery.status != "pending") {
+            return deliveries;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    panic!("Deliveries of webhook {} are still pending", webhook_id);
+}
+
+#[tokio::test]
+async fn test_webhook_delivery() {
+    let stand_in = StandIn { failures_left: Arc::new(Mutex::new(1)), ..Default::default() };
+    let url = start_stand_in(stand_in.clone()).await;
+    let secret = format!("secret_{}", (100000..999999).fake::<u32>());
+
+    let (status, webhook) = common::helper::create_webhook(&format!(
+        r#"{{"url":"{}","secret":"{}","events":["execution.finished","test.new_failure","execution.threshold_breached"],"min_pass_rate":0.9}}"#,
+        url, secret
+    ))
+        .await
+        .expect("Failed to create webhook");
+    assert_eq!(status, 201);
+    assert!(webhook.get("secret").is_none(), "The secret must not be returned");
+    let webhook: Webhook = serde_json::from_value(webhook).expect("Expected a webhook");
+
+    let execution = common::helper::create_execution(r#"{"name":"Execution with Webhook","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+
+    let prefix = format!("hooked_{}", (100000..999999).fake::<u32>());
+    let test_results = [
+        format!(r#"{{"name":"{}_pass","platform":"web","status":"P"}}"#, prefix),
+        format!(r#"{{"name":"{}_fail","platform":"web","status":"F"}}"#, prefix),
+    ];
+    common::helper::stream_create_results(execution_id, test_results.iter().map(String::as_str).collect())
+        .await
+        .expect("Failed to send stream request")
+        .expect("Expected stream response");
+
+    // finishing right after the stream, the results still in the writer queue are written first
+    let (status, finished) = common::helper::finish_execution(execution_id).await.expect("Failed to finish execution");
+    assert_eq!(status, 200);
+    assert!(finished["time_finished"].as_i64().is_some());
+
+    // an execution only finishes once
+    let (status, _) = common::helper::finish_execution(execution_id).await.expect("Failed to finish execution");
+    assert_eq!(status, 409);
+
+    let deliveries = wait_for_deliveries(webhook.id, &[execution_id], 3).await;
+    assert_eq!(deliveries.len(), 3);
+    assert!(deliveries.iter().all(|delivery| delivery.status == "delivered" && delivery.last_status == Some(200)));
+
+    let events = accepted_events(&stand_in, execution_id);
+    let names: Vec<&str> = events.iter().map(|(event, _)| event.as_str()).collect();
+    assert_eq!(names, vec!["execution.finished", "execution.threshold_breached", "test.new_failure"]);
+
+    let (_, finished_event) = &events[0];
+    assert_eq!(finished_event["event"], "execution.finished");
+    assert_eq!(finished_event["data"]["summary"]["pass"], 1);
+    assert_eq!(finished_event["data"]["summary"]["fail"], 1);
+
+    let (_, breached_event) = &events[1];
+    assert_eq!(breached_event["data"]["min_pass_rate"], 0.9);
+
+    let (_, new_failure_event) = &events[2];
+    let tests = new_failure_event["data"]["tests"].as_array().expect("Expected the new failures");
+    assert_eq!(tests.len(), 1);
+    assert_eq!(tests[0]["name"], format!("{}_fail", prefix));
+
+    // every request is signed with the secret
+    let first = {
+        let received = stand_in.received.lock().unwrap();
+        assert!(received.iter().all(|request| verify(&secret, request)));
+        assert!(!verify("wrong secret", &received[0]));
+        received[0].delivery.clone()
+    };
+
+    // the first request was refused, it is sent again after the backoff
+    let mut retried = None;
+    for _ in 0..MAX_WAIT_IN_SEC {
+        retried = common::helper::get_webhook_deliveries(webhook.id, &[("limit", "100")])
+            .await
+            .expect("Failed to get deliveries")
+            .items
+            .into_iter()
+            .find(|delivery| delivery.id.to_string() == first && delivery.status != "pending");
+        if retried.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    let retried = retried.expect("Expected the first delivery to be retried");
+    assert_eq!(retried.status, "delivered");
+    assert_eq!(retried.attempts, 2);
+    assert_eq!(stand_in.received.lock().unwrap().iter().filter(|request| request.delivery == first).count(), 2);
+
+    let failed_only = common::helper::get_webhook_deliveries(webhook.id, &[("status", "failed")])
+        .await
+        .expect("Failed to get deliveries");
+    assert_eq!(failed_only.total, 0);
+
+    assert!(common::helper::delete_webhook(webhook.id).await.expect("Failed to delete webhook"));
+}
+
+#[tokio::test]
+async fn test_webhook_new_failures_only() {
+    let stand_in = StandIn::default();
+    let url = start_stand_in(stand_in.clone()).await;
+
+    let (status, webhook) = common::helper::create_webhook(&format!(
+        r#"{{"url":"{}","secret":"s3cret","events":["test.new_failure"]}}"#, url
+    ))
+        .await
+        .expect("Failed to create webhook");
+    assert_eq!(status, 201);
+    let webhook: Webhook = serde_json::from_value(webhook).expect("Expected a webhook");
+
+    let prefix = format!("refailed_{}", (100000..999999).fake::<u32>());
+    let mut execution_ids = Vec::new();
+    let runs = [
+        vec![format!("{}_a", prefix)],
+        vec![format!("{}_a", prefix), format!("{}_b", prefix)],
+    ];
+    for failed in &runs {
+        let execution = common::helper::create_execution(r#"{"name":"Execution with New Failures","created_by":"test-user"}"#)
+            .await
+            .expect("Failed to create execution")
+            .expect("Expected execution to be created");
+        let execution_id = execution.id.expect("Execution should have an ID");
+        let test_results: Vec<String> = failed
+            .iter()
+            .map(|name| format!(r#"{{"name":"{}","platform":"web","status":"F"}}"#, name))
+            .collect();
+        common::helper::stream_create_results(execution_id, test_results.iter().map(String::as_str).collect())
+            .await
+            .expect("Failed to send stream request")
+            .expect("Expected stream response");
+
+        let (status, _) = common::helper::finish_execution(execution_id).await.expect("Failed to finish execution");
+        assert_eq!(status, 200);
+        execution_ids.push(execution_id);
+    }
+
+    wait_for_deliveries(webhook.id, &execution_ids, 2).await;
+
+    // the test failing again isn't new anymore
+    for (execution_id, expected) in execution_ids.iter().zip([format!("{}_a", prefix), format!("{}_b", prefix)]) {
+        let events = accepted_events(&stand_in, *execution_id);
+        assert_eq!(events.len(), 1);
+        let names: Vec<&str> = events[0].1["data"]["tests"]
+            .as_array()
+            .expect("Expected the new failures")
+            .iter()
+            .filter_map(|test| test["name"].as_str())
+            .collect();
+        assert_eq!(names, vec![expected.as_str()]);
+    }
+
+    assert!(common::helper::delete_webhook(webhook.id).await.expect("Failed to delete webhook"));
+}
+
+#[tokio::test]
+async fn test_webhook_to_private_address() {
+    // 10.0.0.1 isn't in the allowed_hosts of dev.toml, nothing is sent to it
+    let (status, webhook) = common::helper::create_webhook(
+        r#"{"url":"http://10.0.0.1/hook","secret":"s3cret","events":["execution.finished"]}"#
+    )
+        .await
+        .expect("Failed to create webhook");
+    assert_eq!(status, 201);
+    let webhook: Webhook = serde_json::from_value(webhook).expect("Expected a webhook");
+
+    let execution = common::helper::create_execution(r#"{"name":"Execution for Private Hook","created_by":"test-user"}"#)
+        .await
+        .expect("Failed to create execution")
+        .expect("Expected execution to be created");
+    let execution_id = execution.id.expect("Execution should have an ID");
+    let (status, _) = common::helper::finish_execution(execution_id).await.expect("Failed to finish execution");
+    assert_eq!(status, 200);
+
+    let mut attempted = None;
+    for _ in 0..MAX_WAIT_IN_SEC {
+        attempted = common::helper::get_webhook_deliveries(webhook.id, &[("limit", "100")])
+            .await
+            .expect("Failed to get deliveries")
+            .items
+            .into_iter()
+            .find(|delivery| {
+                let payload: Value = serde_json::from_str(&delivery.payload).unwrap();
+                payload["data"]["execution"]["id"].as_i64() == Some(execution_id) && delivery.attempts > 0
+            });
+        if attempted.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    let attempted = attempted.expect("Expected the delivery to be attempted");
+    assert_ne!(attempted.status, "delivered");
+    assert_eq!(attempted.last_status, None);
+    assert!(attempted.last_error.as_deref().is_some_and(|error| error.contains("isn't a public address")));
+
+    assert!(common::helper::delete_webhook(webhook.id).await.expect("Failed to delete webhook"));
+}
+
+#[tokio::test]
+async fn test_webhook_crud_and_validation() {
+    let long_url = format!(r#"{{"url":"https://example.com/{}","secret":"s","events":["execution.finished"]}}"#, "a".repeat(2048));
+    let invalid = [
+        (r#"{"url":"ftp://example.com","secret":"s","events":["execution.finished"]}"#, "url"),
+        (r#"{"url":"http://","secret":"s","events":["execution.finished"]}"#, "url"),
+        (r#"{"url":"http://example.com","secret":" ","events":["execution.finished"]}"#, "secret"),
+        (r#"{"url":"http://example.com","secret":"s","events":[]}"#, "events"),
+        (r#"{"url":"http://example.com","secret":"s","events":["execution.started"]}"#, "events"),
+        (r#"{"url":"http://example.com","secret":"s","events":["execution.threshold_breached"]}"#, "min_pass_rate"),
+        (r#"{"url":"http://example.com","secret":"s","events":["execution.finished"],"min_pass_rate":1.5}"#, "min_pass_rate"),
+        (long_url.as_str(), "url"),
+        (r#"{"url":"http://example.com","events":["execution.finished"]}"#, "secret"),
+    ];
+    for (webhook_json, field) in invalid {
+        let (status, body) = common::helper::create_webhook(webhook_json).await.expect("Failed to send request");
+        assert_eq!(status, 400, "{}", webhook_json);
+        assert_eq!(body["error"], "VALIDATION_ERROR", "{}", webhook_json);
+        assert_eq!(body["field"], field, "{}", webhook_json);
+    }
+
+    let (status, webhook) = common::helper::create_webhook(
+        r#"{"url":"https://example.com/hook","secret":"s","events":["execution.finished"]}"#
+    )
+        .await
+        .expect("Failed to create webhook");
+    assert_eq!(status, 201);
+    let webhook: Webhook = serde_json::from_value(webhook).expect("Expected a webhook");
+
+    let fetched = common::helper::get_webhook(webhook.id)
+        .await
+        .expect("Failed to get webhook")
+        .expect("Expected the webhook");
+    assert_eq!(fetched.url, "https://example.com/hook");
+    assert_eq!(fetched.events.0, vec!["execution.finished".to_string()]);
+
+    assert!(common::helper::delete_webhook(webhook.id).await.expect("Failed to delete webhook"));
+    assert!(common::helper::get_webhook(webhook.id).await.expect("Failed to get webhook").is_none());
+    assert!(!common::helper::delete_webhook(webhook.id).await.expect("Failed to delete webhook"));
+
+    let (status, _) = common::helper::finish_execution(i64::MAX).await.expect("Failed to send request");
+    assert_eq!(status, 404);
+}